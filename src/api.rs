@@ -1,6 +1,9 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
-use crate::table::{ColumnValue, Table, TableDefinition, TableEntry};
+use crate::table::{ColumnType, ColumnValue, Table, TableDefinition, TableEntry};
 
 #[derive(Debug, Clone)]
 pub enum Comp<T> {
@@ -31,6 +34,125 @@ impl<T> Comp<T> {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Operator {
+    Le,
+    Ge,
+    Leq,
+    Geq,
+    #[default]
+    Eq,
+    Neq,
+    In,
+    Nin,
+    Between,
+}
+
+impl Operator {
+    pub const ALL: [Operator; 9] = [
+        Operator::Eq,
+        Operator::Neq,
+        Operator::Le,
+        Operator::Leq,
+        Operator::Ge,
+        Operator::Geq,
+        Operator::In,
+        Operator::Nin,
+        Operator::Between,
+    ];
+
+    // number of operands, none meaning any amount
+    pub fn arity(&self) -> Option<usize> {
+        match self {
+            Operator::In | Operator::Nin => None,
+            Operator::Between => Some(2),
+            _ => Some(1),
+        }
+    }
+
+    pub fn comp(&self, mut values: Vec<ColumnValue>) -> Option<Comp<ColumnValue>> {
+        if self.arity().is_some_and(|arity| arity != values.len()) {
+            return None;
+        }
+
+        let comp = match self {
+            Operator::In => Comp::In(values),
+            Operator::Nin => Comp::Nin(values),
+            Operator::Between => {
+                let max = values.pop()?;
+                let min = values.pop()?;
+                Comp::Between(min, max)
+            },
+            _ => {
+                let value = values.pop()?;
+                match self {
+                    Operator::Le => Comp::Le(value),
+                    Operator::Ge => Comp::Ge(value),
+                    Operator::Leq => Comp::Leq(value),
+                    Operator::Geq => Comp::Geq(value),
+                    Operator::Eq => Comp::Eq(value),
+                    Operator::Neq => Comp::Neq(value),
+                    _ => unreachable!(),
+                }
+            },
+        };
+
+        Some(comp)
+    }
+}
+
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Operator::Le => "<",
+            Operator::Ge => ">",
+            Operator::Leq => "<=",
+            Operator::Geq => ">=",
+            Operator::Eq => "==",
+            Operator::Neq => "!=",
+            Operator::In => "in",
+            Operator::Nin => "not in",
+            Operator::Between => "between",
+        })
+    }
+}
+
+impl Comp<ColumnValue> {
+    // the inverse of `Operator::comp`
+    pub fn split(&self) -> (Operator, Vec<ColumnValue>) {
+        match self.clone() {
+            Comp::Le(value) => (Operator::Le, vec![value]),
+            Comp::Ge(value) => (Operator::Ge, vec![value]),
+            Comp::Leq(value) => (Operator::Leq, vec![value]),
+            Comp::Geq(value) => (Operator::Geq, vec![value]),
+            Comp::Eq(value) => (Operator::Eq, vec![value]),
+            Comp::Neq(value) => (Operator::Neq, vec![value]),
+            Comp::In(values) => (Operator::In, values),
+            Comp::Nin(values) => (Operator::Nin, values),
+            Comp::Between(min, max) => (Operator::Between, vec![min, max]),
+        }
+    }
+}
+
+impl std::fmt::Display for Comp<ColumnValue> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |values: &Vec<ColumnValue>| {
+            values.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            Comp::In(values) | Comp::Nin(values) => write!(f, "{} [{}]", self.operator(), list(values)),
+            Comp::Between(min, max) => write!(f, "{} {}..{}", self.operator(), min, max),
+            Comp::Le(value) | Comp::Ge(value) | Comp::Leq(value) | Comp::Geq(value) | Comp::Eq(value) | Comp::Neq(value) => {
+                write!(f, "{} {}", self.operator(), value)
+            },
+        }
+    }
+}
+
 impl serde::Serialize for Comp<ColumnValue> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -55,7 +177,45 @@ impl serde::Serialize for Comp<ColumnValue> {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+// the inverse of serializing, for filters saved in the settings
+impl<'de> serde::Deserialize<'de> for Comp<ColumnValue> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>
+    {
+        use serde::de::Error as _;
+
+        let (operator, value): (String, Value) = serde::Deserialize::deserialize(deserializer)?;
+
+        let operator = match operator.as_str() {
+            "<" => Operator::Le,
+            ">" => Operator::Ge,
+            "<=" => Operator::Leq,
+            ">=" => Operator::Geq,
+            "==" => Operator::Eq,
+            "!=" => Operator::Neq,
+            "in" => Operator::In,
+            "not_in" => Operator::Nin,
+            "range" => Operator::Between,
+            operator => return Err(D::Error::custom(format!("unknown operator `{}`", operator))),
+        };
+
+        let values = match (operator.arity(), value) {
+            (Some(1), value) => vec![value],
+            (_, Value::Array(values)) => values,
+            (_, value) => return Err(D::Error::custom(format!("expected a list of operands, got `{}`", value))),
+        };
+
+        let values = values.into_iter()
+            .map(|value| ColumnValue::try_from_value(value, None).ok().flatten())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| D::Error::custom("unsupported operand"))?;
+
+        operator.comp(values).ok_or_else(|| D::Error::custom("wrong number of operands"))
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Filter(HashMap<String, Comp<ColumnValue>>);
 
 impl Filter {
@@ -66,6 +226,68 @@ impl Filter {
     pub fn insert(&mut self, column: &str, comp: Comp<ColumnValue>) {
         self.0.insert(column.to_owned(), comp);
     }
+
+    pub fn remove(&mut self, column: &str) {
+        self.0.remove(column);
+    }
+
+    pub fn get(&self, column: &str) -> Option<&Comp<ColumnValue>> {
+        self.0.get(column)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Comp<ColumnValue>)> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+// chainable shorthands for building filters in code, a later condition on the same column replaces the earlier one
+impl Filter {
+    pub fn with(mut self, column: &str, comp: Comp<ColumnValue>) -> Self {
+        self.insert(column, comp);
+        self
+    }
+
+    pub fn eq(self, column: &str, value: impl Into<ColumnValue>) -> Self {
+        self.with(column, Comp::Eq(value.into()))
+    }
+
+    #[allow(dead_code)]
+    pub fn neq(self, column: &str, value: impl Into<ColumnValue>) -> Self {
+        self.with(column, Comp::Neq(value.into()))
+    }
+
+    #[allow(dead_code, clippy::wrong_self_convention)]
+    pub fn is_in<V: Into<ColumnValue>>(self, column: &str, values: impl IntoIterator<Item = V>) -> Self {
+        self.with(column, Comp::In(values.into_iter().map(Into::into).collect()))
+    }
+
+    #[allow(dead_code)]
+    pub fn between(self, column: &str, min: impl Into<ColumnValue>, max: impl Into<ColumnValue>) -> Self {
+        self.with(column, Comp::Between(min.into(), max.into()))
+    }
+}
+
+// fetched entries, `total` is set when the server reports how many rows match overall
+#[derive(Debug, Clone)]
+pub struct Page {
+    pub entries: Vec<TableEntry>,
+    pub total: Option<usize>,
+}
+
+// the items endpoint answers with either a bare array or an object carrying the total
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Items {
+    Plain(Vec<serde_json::Map<String, Value>>),
+    Paged {
+        items: Vec<serde_json::Map<String, Value>>,
+        // untagged enums can't read arbitrary precision numbers into integers, only into `Number`
+        total: Option<serde_json::Number>,
+    },
 }
 
 pub enum Selection {
@@ -80,53 +302,544 @@ pub enum Error {
     Request(#[from] reqwest::Error),
     #[error("json error: `{0}`")]
     Json(#[from] serde_json::Error),
-    #[error("error: `{0}`")]
-    Response(String),
+    #[error("error: `{}`", .0.message())]
+    Response(Box<Failure>),
+    #[error("rejected columns: `{}`", .0.join(", "))]
+    Rejected(Vec<String>, Box<Failure>),
+    #[error("unsupported value in column `{0}`")]
+    Value(String),
+    #[error("`{1}` can't be sent as {2} to column `{0}` without losing precision")]
+    Lossy(String, ColumnValue, ColumnType),
+    #[error("response is not json: `{0}`")]
+    NotJson(String),
+    #[error("unsupported url: `{0}`")]
+    Url(String),
+    #[error("dry run, request not sent")]
+    DryRun(String),
+    #[error("response too large — refine your query")]
+    TooLarge,
+    #[error("no answer within {0} seconds — the server may be busy, or raise the timeout in settings")]
+    Timeout(u64),
+}
+
+impl Error {
+    // the token is missing or has expired
+    pub fn is_unauthorized(&self) -> bool {
+        match self {
+            Error::Response(failure) | Error::Rejected(_, failure) => failure.status == reqwest::StatusCode::UNAUTHORIZED,
+            _ => false,
+        }
+    }
+
+    // plain-language explanation for requests that never got an answer
+    pub fn hint(&self) -> Option<&'static str> {
+        let Error::Request(err) = self else { return None; };
+
+        // reqwest has no dns kind, the resolver's message is somewhere down the source chain
+        let mut source = std::error::Error::source(err);
+        while let Some(inner) = source {
+            let text = inner.to_string().to_lowercase();
+            if text.contains("dns") || text.contains("lookup") || text.contains("resolve") {
+                return Some("couldn't find the server, check the host name in the url");
+            }
+            source = inner.source();
+        }
+
+        if err.is_timeout() {
+            Some("the server took too long to answer, it may be overloaded or unreachable")
+        }
+        else if err.is_connect() {
+            Some("couldn't reach the server, is it running and is the url right?")
+        }
+        else if err.is_builder() {
+            Some("the url isn't valid")
+        }
+        else {
+            None
+        }
+    }
+
+    // everything worth pasting into a bug report, with credentials removed from urls
+    pub fn report(&self) -> String {
+        match self {
+            Error::Response(failure) | Error::Rejected(_, failure) => failure.report(),
+            Error::DryRun(preview) => preview.clone(),
+            Error::Request(err) => {
+                let text = err.to_string();
+
+                match err.url() {
+                    Some(url) => text.replace(url.as_str(), &redact(url)),
+                    None => text,
+                }
+            },
+            err => err.to_string(),
+        }
+    }
+}
+
+// a request the server answered with an error status
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub method: reqwest::Method,
+    pub url: reqwest::Url,
+    pub status: reqwest::StatusCode,
+    pub body: String,
+}
+
+impl Failure {
+    pub fn report(&self) -> String {
+        format!("{} {}\n{}\n\n{}", self.method, redact(&self.url), self.status, self.body)
+    }
+
+    // the message out of a json error envelope such as `{"error": "...", "code": 123}`,
+    // none when the body is plain text or some other json
+    pub fn envelope(&self) -> Option<String> {
+        let Ok(Value::Object(object)) = serde_json::from_str(&self.body) else { return None; };
+
+        let text = |value: &Value| match value {
+            Value::String(text) => Some(text.clone()),
+            Value::Number(number) => Some(number.to_string()),
+            _ => None,
+        };
+
+        // the error may be the message itself or an object holding it
+        let inner = match object.get("error") {
+            Some(Value::Object(inner)) => inner,
+            _ => &object,
+        };
+
+        let message = ["error", "message", "detail", "msg"].into_iter()
+            .find_map(|key| inner.get(key).and_then(text))?;
+
+        let code = ["code", "status"].into_iter()
+            .find_map(|key| inner.get(key).or_else(|| object.get(key)).and_then(text));
+
+        match code {
+            Some(code) => Some(format!("{} (code {})", message, code)),
+            None => Some(message),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        self.envelope().unwrap_or_else(|| self.body.clone())
+    }
+}
+
+// hide passwords and token-like query parameters
+fn redact(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+
+    if url.password().is_some() {
+        let _ = url.set_password(Some("redacted"));
+    }
+
+    let pairs: Vec<(String, String)> = url.query_pairs()
+        .map(|(key, value)| {
+            let secret = ["token", "key", "auth"].into_iter()
+                .any(|word| key.to_lowercase().contains(word));
+
+            (key.into_owned(), if secret { "redacted".to_owned() } else { value.into_owned() })
+        })
+        .collect();
+
+    if !pairs.is_empty() {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    url.to_string()
+}
+
+// the request as it would go over the wire, followed by an equivalent curl command
+fn preview(request: &reqwest::Request) -> String {
+    let url = redact(request.url());
+
+    let headers: Vec<_> = request.headers().iter()
+        .map(|(name, value)| {
+            let value = if *name == reqwest::header::AUTHORIZATION {
+                "Bearer redacted"
+            }
+            else {
+                value.to_str().unwrap_or("<binary>")
+            };
+
+            (name.as_str(), value)
+        })
+        .collect();
+
+    let body = request.body()
+        .and_then(|body| body.as_bytes())
+        .map(String::from_utf8_lossy);
+
+    let pretty = body.as_deref().map(pretty);
+
+    let mut text = format!("{} {}\n", request.method(), url);
+    for (name, value) in &headers {
+        text.push_str(&format!("{}: {}\n", name, value));
+    }
+    if let Some(pretty) = &pretty {
+        text.push_str(&format!("\n{}\n", pretty));
+    }
+
+    text.push_str(&format!("\ncurl -X {} '{}'", request.method(), url));
+    for (name, value) in &headers {
+        text.push_str(&format!(" -H '{}: {}'", name, value));
+    }
+    if let Some(body) = &body {
+        text.push_str(&format!(" -d '{}'", body.replace('\'', "'\\''")));
+    }
+
+    text
+}
+
+// json bodies indented, anything else as it is
+fn pretty(body: &str) -> String {
+    serde_json::from_str::<Value>(body)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| body.to_owned())
+}
+
+// the body of a request to stderr under the method and url it's sent to, cut after `max` characters
+fn log_body(request: &reqwest::Request, max: usize) {
+    let Some(body) = request.body().and_then(|body| body.as_bytes()) else { return; };
+
+    let body = pretty(&String::from_utf8_lossy(body));
+    let mut logged: String = body.chars().take(max).collect();
+
+    if logged.len() < body.len() {
+        logged.push_str("…(truncated)");
+    }
+
+    eprintln!("{} {}\n{}", request.method(), redact(request.url()), logged);
+}
+
+const SNIPPET_LENGTH: usize = 120;
+// requests in flight at once unless configured otherwise
+pub const MAX_REQUESTS: usize = 4;
+// seconds a request may take, body included, unless configured otherwise
+pub const TIMEOUT: u64 = 60;
+// megabytes a response body may take up unless configured otherwise
+pub const MAX_RESPONSE: usize = 100;
+
+// json object sent to create or update a row, nulls included
+pub fn body(values: TableEntry) -> Value {
+    let body: serde_json::Map<String, Value> = values.into_iter()
+        .map(|(column, value)| (column, value.map_or(Value::Null, Into::into)))
+        .collect();
+
+    Value::Object(body)
+}
+
+// how filters are sent to the items endpoint, some proxies drop bodies of get requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum FilterTransport {
+    #[default]
+    Body,
+    Query,
+}
+
+impl FilterTransport {
+    pub const ALL: [FilterTransport; 2] = [
+        FilterTransport::Body,
+        FilterTransport::Query,
+    ];
+}
+
+impl std::fmt::Display for FilterTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FilterTransport::Body => "JSON body",
+            FilterTransport::Query => "Query string",
+        })
+    }
+}
+
+// major versions of the backend api this client is written against
+pub const API_VERSIONS: std::ops::RangeInclusive<u64> = 1..=1;
+
+// what the backend reports about itself on `api/capabilities`, e.g. `{"version": "1.2.0", "features": ["count", "paging", "changes"]}`
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct Capabilities {
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+impl Capabilities {
+    pub fn has(&self, feature: &str) -> bool {
+        self.features.iter().any(|known| known == feature)
+    }
+
+    // a warning when the backend's major version is one this client wasn't written for
+    pub fn compatibility(&self) -> Option<String> {
+        let version = self.version.as_deref()?;
+        let major: u64 = version.trim_start_matches('v').split('.').next()?.parse().ok()?;
+
+        if major < *API_VERSIONS.start() {
+            Some(format!("the backend api is version {}, older than this client supports, some features may fail", version))
+        }
+        else if major > *API_VERSIONS.end() {
+            Some(format!("the backend api is version {}, newer than this client supports, some features may be missing", version))
+        }
+        else {
+            None
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Client {
     pub url: String,
+    // path the api is mounted under on the server, e.g. `v2`, empty for the root
+    pub prefix: String,
+    // sent as a bearer token when set
+    pub token: Option<String>,
+    pub filter_transport: FilterTransport,
+    // build requests but show them instead of sending
+    pub dry_run: bool,
+    // characters of each request body written to stderr, none logs nothing
+    pub log_bodies: Option<usize>,
+    // none waits as long as the connection stays up
+    pub timeout: Option<Duration>,
+    // bytes of a response body read before giving up on it, none reads any size
+    pub max_response: Option<usize>,
+    // none until probed, or when the backend doesn't report them
+    pub capabilities: Option<Capabilities>,
     client: reqwest::Client,
+    // shared by every clone, so the limit holds across the whole session
+    limiter: Arc<tokio::sync::Semaphore>,
+    max_requests: usize,
 }
 
 impl Client {
     pub fn new(url: String) -> Self {
         Self {
             url: url,
-            client: reqwest::Client::new(),
+            prefix: String::new(),
+            token: None,
+            filter_transport: FilterTransport::default(),
+            dry_run: false,
+            log_bodies: None,
+            timeout: Some(Duration::from_secs(TIMEOUT)),
+            max_response: Some(MAX_RESPONSE << 20),
+            capabilities: None,
+            client: Self::http_client(),
+            limiter: Arc::new(tokio::sync::Semaphore::new(MAX_REQUESTS)),
+            max_requests: MAX_REQUESTS,
+        }
+    }
+
+    // compressed responses are accepted and unpacked before the body is read, so parsing never sees them
+    fn http_client() -> reqwest::Client {
+        reqwest::Client::builder()
+            .gzip(true)
+            .deflate(true)
+            .brotli(true)
+            .build()
+            .expect("tls backend or resolver unavailable")
+    }
+
+    // requests already waiting on the old limit still go through it
+    pub fn set_max_requests(&mut self, max_requests: usize) {
+        let max_requests = max_requests.max(1);
+
+        if max_requests != self.max_requests {
+            self.limiter = Arc::new(tokio::sync::Semaphore::new(max_requests));
+            self.max_requests = max_requests;
+        }
+    }
+
+    // trim pasted whitespace and trailing slashes, defaulting to http when no scheme is given
+    pub fn normalize_url(url: &str) -> Result<String, Error> {
+        let url = url.trim().trim_end_matches('/');
+
+        let url = if url.contains("://") {
+            url.to_owned()
+        }
+        else {
+            format!("http://{}", url)
+        };
+
+        if url.starts_with("http://") || url.starts_with("https://") {
+            Ok(url)
+        }
+        else {
+            Err(Error::Url(url))
+        }
+    }
+
+    fn endpoint(&self, path: &str) -> Result<String, Error> {
+        let url = Self::normalize_url(&self.url)?;
+
+        match self.prefix.trim_matches('/') {
+            "" => Ok(format!("{}/{}", url, path)),
+            prefix => Ok(format!("{}/{}/{}", url, prefix, path)),
+        }
+    }
+
+    // every request starts here so they all share the base url and headers
+    fn request(&self, method: reqwest::Method, path: &str) -> Result<reqwest::RequestBuilder, Error> {
+        let url = self.endpoint(path)?;
+
+        let mut builder = self.client
+            .request(method, url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(reqwest::header::ACCEPT, "application/json");
+
+        if let Some(token) = &self.token {
+            builder = builder.bearer_auth(token);
+        }
+
+        Ok(builder)
+    }
+
+    async fn send(&self, builder: reqwest::RequestBuilder) -> Result<String, Error> {
+        let builder = match self.timeout {
+            Some(timeout) => builder.timeout(timeout),
+            None => builder,
+        };
+
+        let request = builder.build()?;
+        let method = request.method().clone();
+
+        if self.dry_run {
+            return Err(Error::DryRun(preview(&request)));
+        }
+
+        if let Some(max) = self.log_bodies {
+            log_body(&request, max);
+        }
+
+        // held until the body is read, the semaphore is never closed
+        let _permit = self.limiter.acquire().await;
+
+        let response = self.client.execute(request).await.map_err(|err| self.timed_out(err))?;
+
+        self.response_text(method, response).await
+    }
+
+    // read in chunks and given up on once past the size limit, a huge body never ends up in memory whole
+    async fn response_text(&self, method: reqwest::Method, mut response: reqwest::Response) -> Result<String, Error> {
+        let status = response.status();
+        let url = response.url().clone();
+
+        let too_large = |size: u64| self.max_response.is_some_and(|max| size > max as u64);
+
+        if response.content_length().is_some_and(too_large) {
+            return Err(Error::TooLarge);
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|err| self.timed_out(err))? {
+            body.extend_from_slice(&chunk);
+
+            if too_large(body.len() as u64) {
+                return Err(Error::TooLarge);
+            }
+        }
+
+        let text = String::from_utf8_lossy(&body).into_owned();
+
+        if status.is_success() { Ok(text) }
+        else { Err(Error::Response(Box::new(Failure { method, url, status, body: text }))) }
+    }
+
+    // a timeout of our own making reads better with the limit that was hit
+    fn timed_out(&self, err: reqwest::Error) -> Error {
+        match self.timeout {
+            Some(timeout) if err.is_timeout() => Error::Timeout(timeout.as_secs()),
+            _ => Error::Request(err),
         }
     }
 
-    async fn response_text(response: reqwest::Response) -> Result<String, Error> {
-        let is_success = response.status().is_success();
+    // tell a non-json body (e.g. an html page served with 200) apart from json of the wrong shape
+    fn parse<T: DeserializeOwned>(text: &str) -> Result<T, Error> {
+        let value: Value = serde_json::from_str(text).map_err(|_| {
+            let snippet: String = text.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .chars()
+                .take(SNIPPET_LENGTH)
+                .collect();
 
-        let text = response.text().await?;
+            Error::NotJson(snippet)
+        })?;
 
-        if is_success { Ok(text) }
-        else { Err(Error::Response(text)) }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    // the schema as the server sends it, pretty-printed but otherwise untouched
+    pub async fn tables_raw(&self) -> Result<String, Error> {
+        let builder = self.request(reqwest::Method::GET, "api/tables")?;
+
+        let text = self.send(builder).await?;
+
+        let value: Value = Self::parse(&text)?;
+
+        Ok(serde_json::to_string_pretty(&value)?)
     }
 
     pub async fn tables(&self) -> Result<Vec<TableDefinition>, Error> {
-        let url = format!("{}/api/tables", self.url);
-        let response = self.client.get(url)
-            .header("Content-Type", "application/json")
-            .send().await?;
+        let text = self.tables_raw().await?;
 
-        let text = Self::response_text(response).await?;
+        Self::tables_from(&text)
+    }
 
-        let tables = serde_json::from_str(&text)?;
+    // the table definitions out of a schema fetched by `tables_raw`
+    pub fn tables_from(text: &str) -> Result<Vec<TableDefinition>, Error> {
+        let tables = Self::parse(text)?;
 
         let entries = TableDefinition::from_vec(tables);
 
         Ok(entries)
     }
 
-    pub async fn get(&self, table_name: &str, selection: Selection) -> Result<Vec<TableEntry>, Error> {
+    // none when the backend has no capabilities endpoint
+    pub async fn capabilities(&self) -> Result<Option<Capabilities>, Error> {
+        let builder = self.request(reqwest::Method::GET, "api/capabilities")?;
+
+        let text = match self.send(builder).await {
+            Ok(text) => text,
+            Err(Error::Response(failure)) if failure.status == reqwest::StatusCode::NOT_FOUND => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Some(Self::parse(&text)?))
+    }
+
+    // whether an optional feature may be used, the baseline for backends that don't report capabilities is to try
+    // each one, as every one of them falls back on its own when it's missing
+    pub fn supports(&self, feature: &str) -> bool {
+        self.capabilities.as_ref().is_none_or(|capabilities| capabilities.has(feature))
+    }
+
+    // number of rows in a table, none when the server has no count endpoint
+    pub async fn count(&self, table_name: &str) -> Result<Option<usize>, Error> {
+        let builder = self.request(reqwest::Method::GET, &format!("api/count/{}", table_name))?;
+
+        let text = match self.send(builder).await {
+            Ok(text) => text,
+            Err(Error::Response(failure)) if failure.status == reqwest::StatusCode::NOT_FOUND => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Some(Self::parse(&text)?))
+    }
+
+    // `limit` caps how many entries are requested starting at `offset`, lookups by id ignore both
+    pub async fn get(&self, table: &Table, selection: Selection, limit: Option<usize>, offset: usize) -> Result<Page, Error> {
+        let table_name = &table.table;
+
+        // an empty filter matches everything, so treat it as such
+        let selection = match selection {
+            Selection::Filter(filter) if filter.is_empty() => Selection::All,
+            selection => selection,
+        };
+
         // set endpoint based on selection
-        let url = match &selection {
-            Selection::Id(id) => format!("{}/api/item/{}/{}", self.url, table_name, id),
-            _ => format!("{}/api/items/{}", self.url, table_name),
+        let path = match &selection {
+            Selection::Id(id) => format!("api/item/{}/{}", table_name, id),
+            _ => format!("api/items/{}", table_name),
         };
 
         let is_by_id = matches!(selection, Selection::Id(_));
@@ -137,43 +850,478 @@ impl Client {
             Selection::Filter(filter) => Some(serde_json::to_string(filter)?), // use filter
         };
 
-        let mut builder = self.client
-            .get(url)
-            .header("Content-Type", "application/json");
+        let mut builder = self.request(reqwest::Method::GET, &path)?;
+
+        match (&selection, self.filter_transport) {
+            // one parameter per column holding the same json the body would
+            (Selection::Filter(filter), FilterTransport::Query) => {
+                let query = filter.iter()
+                    .map(|(column, comp)| Ok((column.as_str(), serde_json::to_string(comp)?)))
+                    .collect::<Result<Vec<_>, Error>>()?;
 
-        // include body if there is one
-        if let Some(body) = body {
-            builder = builder.body(body);
+                builder = builder.query(&query);
+            },
+            (Selection::All, FilterTransport::Query) => (),
+            // include body if there is one
+            _ => if let Some(body) = body {
+                builder = builder.body(body);
+            },
         }
 
-        let response = builder.send().await?;
-        let text = Self::response_text(response).await?;
+        if let Some(limit) = limit.filter(|_| !is_by_id) {
+            builder = builder.query(&[("limit", limit)]);
+        }
+
+        if offset > 0 && !is_by_id {
+            builder = builder.query(&[("offset", offset)]);
+        }
+
+        // a missing id is an ordinary outcome of a lookup, not a failure
+        let text = match self.send(builder).await {
+            Ok(text) => text,
+            Err(Error::Response(failure)) if is_by_id && failure.status == reqwest::StatusCode::NOT_FOUND => {
+                return Ok(Page { entries: Vec::new(), total: Some(0) });
+            },
+            Err(err) => return Err(err),
+        };
 
         // handle single/multiple entries
-        let items = if is_by_id {
-            let value = serde_json::from_str(&text)?;
-            vec![value]
+        let (mut items, total) = if is_by_id {
+            (vec![Self::parse(&text)?], None)
         }
         else {
-            serde_json::from_str(&text)?
+            match Self::parse(&text)? {
+                Items::Plain(items) => (items, None),
+                Items::Paged { items, total } => (items, total.and_then(|total| total.as_u64()).map(|total| total as usize)),
+            }
         };
 
+        // in case the server doesn't honour the limit
+        if let Some(limit) = limit.filter(|_| !is_by_id) {
+            items.truncate(limit);
+        }
+
+        let entries = items.into_iter()
+            .map(|map| Self::entry(table, map))
+            .collect::<Result<_, Error>>()?;
 
-        let items = items.into_iter()
-            .map(|item| {
-                let map = match item {
-                    Value::Object(map) => map,
-                    _ => unreachable!(),
+        Ok(Page { entries, total })
+    }
+
+    // a json object as a row, typed by the table's columns
+    fn entry(table: &Table, map: serde_json::Map<String, Value>) -> Result<TableEntry, Error> {
+        let mut entry = map.into_iter()
+            .map(|(k, v)| {
+                let ty = table.columns.iter()
+                    .find(|column| column.name == k)
+                    .map(|column| column.ty);
+
+                let value = ColumnValue::try_from_value(v, ty)
+                    .map_err(|_| Error::Value(k.clone()))?;
+
+                Ok((k, value))
+            })
+            .collect::<Result<TableEntry, Error>>()?;
+
+        table.order(&mut entry);
+
+        Ok(entry)
+    }
+
+    // a long-lived stream of changes to a table, a 404 means the server doesn't offer one
+    pub async fn changes(&self, table: &Table) -> Result<ChangeStream, Error> {
+        let mut request = self.request(reqwest::Method::GET, &format!("api/changes/{}", table.table))?.build()?;
+
+        request.headers_mut().insert(reqwest::header::ACCEPT, reqwest::header::HeaderValue::from_static("text/event-stream"));
+
+        if self.dry_run {
+            return Err(Error::DryRun(preview(&request)));
+        }
+
+        // not counted against the request limit, it would hold a slot for as long as it's open
+        let method = request.method().clone();
+        let response = self.client.execute(request).await?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            let url = response.url().clone();
+            let body = response.text().await?;
+
+            return Err(Error::Response(Box::new(Failure { method, url, status, body })));
+        }
+
+        Ok(ChangeStream { response, table: table.clone(), buffer: Vec::new() })
+    }
+
+    pub async fn update(&self, table: &Table, id: ColumnValue, values: TableEntry) -> Result<(), Error> {
+        let values = Self::coerce(table, values)?;
+
+        let builder = self.request(reqwest::Method::PATCH, &format!("api/item/{}/{}", table.table, id))?;
+
+        let columns: Vec<_> = values.keys().cloned().collect();
+
+        let builder = builder.body(body(values).to_string());
+
+        match self.send(builder).await {
+            Ok(_) => Ok(()),
+            Err(Error::Response(failure)) => Err(Self::rejected(&columns, failure)),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn insert(&self, table: &Table, values: TableEntry) -> Result<(), Error> {
+        let values = Self::coerce(table, values)?;
+
+        let builder = self.request(reqwest::Method::POST, &format!("api/item/{}", table.table))?;
+
+        let columns: Vec<_> = values.keys().cloned().collect();
+
+        let builder = builder.body(body(values).to_string());
+
+        match self.send(builder).await {
+            Ok(_) => Ok(()),
+            Err(Error::Response(failure)) => Err(Self::rejected(&columns, failure)),
+            Err(err) => Err(err),
+        }
+    }
+
+    // values are sent as their column's declared type, so an integral float never reaches an int column as `42.0`
+    fn coerce(table: &Table, values: TableEntry) -> Result<TableEntry, Error> {
+        values.into_iter()
+            .map(|(name, value)| {
+                let ty = table.columns.iter()
+                    .find(|column| column.name == name)
+                    .map(|column| column.ty);
+
+                let value = match (value, ty) {
+                    (Some(value), Some(ty)) => Some(value.coerce(ty).map_err(|value| Error::Lossy(name.clone(), value, ty))?),
+                    (value, _) => value,
                 };
 
-                map.into_iter()
-                    .map(|(k, v)| {
-                        (k, ColumnValue::try_from_value(v).unwrap())
-                    })
-                    .collect()
+                Ok((name, value))
             })
-            .collect();
+            .collect()
+    }
+
+    // an error object keyed by column names tells which of the sent columns were rejected
+    fn rejected(columns: &[String], failure: Box<Failure>) -> Error {
+        let rejected: Vec<_> = match serde_json::from_str(&failure.body) {
+            Ok(Value::Object(map)) => {
+                let map = match map.get("errors") {
+                    Some(Value::Object(errors)) => errors,
+                    _ => &map,
+                };
+
+                columns.iter()
+                    .filter(|column| map.contains_key(column.as_str()))
+                    .cloned()
+                    .collect()
+            },
+            _ => Vec::new(),
+        };
+
+        if rejected.is_empty() {
+            Error::Response(failure)
+        }
+        else {
+            Error::Rejected(rejected, failure)
+        }
+    }
+}
+
+// a row changed by someone else, rows are told apart by their primary key
+#[derive(Debug, Clone)]
+pub enum Change {
+    Insert(TableEntry),
+    Update(TableEntry),
+    Delete(ColumnValue),
+}
+
+// one event as sent on the changes endpoint, e.g. `{"op": "update", "item": {...}}`
+#[derive(serde::Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum ChangeEvent {
+    Insert { item: serde_json::Map<String, Value> },
+    Update { item: serde_json::Map<String, Value> },
+    Delete { id: Value },
+}
+
+impl Change {
+    fn parse(table: &Table, data: &str) -> Result<Self, Error> {
+        let change = match Client::parse(data)? {
+            ChangeEvent::Insert { item } => Change::Insert(Client::entry(table, item)?),
+            ChangeEvent::Update { item } => Change::Update(Client::entry(table, item)?),
+            ChangeEvent::Delete { id } => {
+                let key = table.columns.iter().find(|column| column.primary_key);
+
+                let id = ColumnValue::try_from_value(id, key.map(|column| column.ty))
+                    .ok()
+                    .flatten()
+                    .ok_or_else(|| Error::Value(key.map_or("id".to_owned(), |column| column.name.clone())))?;
+
+                Change::Delete(id)
+            },
+        };
+
+        Ok(change)
+    }
+}
+
+// server-sent events from the changes endpoint, read as they arrive
+pub struct ChangeStream {
+    response: reqwest::Response,
+    table: Table,
+    buffer: Vec<u8>,
+}
+
+impl ChangeStream {
+    // the next change, none once the server closes the stream
+    pub async fn next(&mut self) -> Result<Option<Change>, Error> {
+        loop {
+            // events end with a blank line, carriage returns are dropped as they come in
+            if let Some(end) = self.buffer.windows(2).position(|window| window == b"\n\n") {
+                let event: Vec<u8> = self.buffer.drain(..end + 2).collect();
+                let event = String::from_utf8_lossy(&event);
+
+                let data = event.lines()
+                    .filter_map(|line| line.strip_prefix("data:"))
+                    .map(str::trim_start)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                // comments and keep-alives carry no data
+                if data.is_empty() {
+                    continue;
+                }
+
+                return Change::parse(&self.table, &data).map(Some);
+            }
+
+            match self.response.chunk().await? {
+                Some(chunk) => self.buffer.extend(chunk.iter().filter(|byte| **byte != b'\r')),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use super::*;
+
+    fn response(status: &str, body: &str) -> String {
+        format!("HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body)
+    }
+
+    // a server on a local port answering each connection with the next of `responses`, the requests it got are
+    // sent back as text with lowercase header names
+    fn serve(responses: Vec<String>) -> (String, std::sync::mpsc::Receiver<String>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+
+                let mut request = Vec::new();
+                let mut buffer = [0; 4096];
+
+                // the head, then as much of the body as it announces
+                loop {
+                    let read = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+
+                    let text = String::from_utf8_lossy(&request).to_lowercase();
+                    let Some(end) = text.find("\r\n\r\n") else { continue; };
+
+                    let length = text[..end].lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .map_or(0, |length| length.trim().parse().unwrap());
+
+                    if read == 0 || request.len() >= end + 4 + length {
+                        break;
+                    }
+                }
+
+                let _ = sender.send(String::from_utf8_lossy(&request).to_lowercase());
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        (url, receiver)
+    }
+
+    #[test]
+    fn filter_chains_conditions() {
+        let filter = Filter::new()
+            .eq("category", "weapon")
+            .between("price", 10, 100)
+            .is_in("rarity", ["rare", "epic"])
+            .neq("stock", 0)
+            .eq("category", "armor");
+
+        let text = |column| filter.get(column).map(ToString::to_string);
+        assert_eq!(text("category").as_deref(), Some("== armor"));
+        assert_eq!(text("price").as_deref(), Some("range 10..100"));
+        assert_eq!(text("rarity").as_deref(), Some("in [rare, epic]"));
+        assert_eq!(text("stock").as_deref(), Some("!= 0"));
+        assert_eq!(filter.iter().count(), 4);
+    }
+
+    fn table() -> Table {
+        serde_json::from_value(serde_json::json!({
+            "name": "Item",
+            "table": "item",
+            "polymorphic": null,
+            "columns": [
+                {"name": "id", "type": "int", "optional": false, "primary_key": true, "foreign_keys": [], "mapper": null},
+                {"name": "price", "type": "float", "optional": false, "primary_key": false, "foreign_keys": [], "mapper": null},
+                {"name": "stock", "type": "int", "optional": true, "primary_key": false, "foreign_keys": [], "mapper": null},
+            ],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn coerce_integral_float_to_int() {
+        let values = TableEntry::from([
+            ("stock".to_owned(), Some(ColumnValue::Float(42.0))),
+            ("price".to_owned(), Some(ColumnValue::Float(1.5))),
+        ]);
+
+        let values = Client::coerce(&table(), values).unwrap();
+
+        assert_eq!(values["stock"], Some(ColumnValue::Int(42)));
+        assert_eq!(values["price"], Some(ColumnValue::Float(1.5)));
+        assert_eq!(body(values).to_string(), r#"{"stock":42,"price":1.5}"#);
+    }
+
+    #[test]
+    fn coerce_keeps_nulls_and_unknown_columns() {
+        let values = TableEntry::from([
+            ("stock".to_owned(), None),
+            ("note".to_owned(), Some(ColumnValue::Float(2.0))),
+        ]);
+
+        let values = Client::coerce(&table(), values).unwrap();
+
+        assert_eq!(values["stock"], None);
+        assert_eq!(values["note"], Some(ColumnValue::Float(2.0)));
+    }
+
+    #[test]
+    fn coerce_rejects_lossy_float() {
+        let values = TableEntry::from([("stock".to_owned(), Some(ColumnValue::Float(4.2)))]);
+
+        assert!(matches!(Client::coerce(&table(), values), Err(Error::Lossy(column, _, ColumnType::Int)) if column == "stock"));
+    }
+
+    #[tokio::test]
+    async fn empty_filter_is_sent_like_all() {
+        let (url, requests) = serve(vec![response("200 OK", "[]"), response("200 OK", "[]")]);
+        let client = Client::new(url);
+
+        client.get(&table(), Selection::All, None, 0).await.unwrap();
+        client.get(&table(), Selection::Filter(Filter::new()), None, 0).await.unwrap();
+
+        let all = requests.recv().unwrap();
+        let empty = requests.recv().unwrap();
+
+        assert!(all.starts_with("get /api/items/item http/1.1"));
+        assert!(all.ends_with("\r\n\r\n{}"));
+        assert_eq!(all, empty);
+    }
+
+    #[tokio::test]
+    async fn requests_share_headers_and_prefix() {
+        let (url, requests) = serve(vec![response("200 OK", "[]"), response("200 OK", "[]")]);
+        let mut client = Client::new(url);
+        client.prefix = "/v2/".to_owned();
+        client.token = Some("secret".to_owned());
+
+        client.tables().await.unwrap();
+        client.get(&table(), Selection::All, None, 0).await.unwrap();
+
+        for request in [requests.recv().unwrap(), requests.recv().unwrap()] {
+            assert!(request.contains(" /v2/api/"));
+            assert!(request.contains("\r\ncontent-type: application/json\r\n"));
+            assert!(request.contains("\r\naccept: application/json\r\n"));
+            assert!(request.contains("\r\nauthorization: bearer secret\r\n"));
+        }
+    }
+
+    #[tokio::test]
+    async fn unauthorized_then_retried_with_a_token() {
+        let (url, requests) = serve(vec![response("401 Unauthorized", r#"{"error": "token expired"}"#), response("200 OK", "[]")]);
+        let mut client = Client::new(url);
+        client.token = Some("expired".to_owned());
+
+        let err = client.tables().await.unwrap_err();
+        assert!(err.is_unauthorized());
+        assert!(crate::banner::ErrorMessage::from(err).unauthorized);
+
+        client.token = Some("fresh".to_owned());
+        assert!(client.tables().await.unwrap().is_empty());
+
+        assert!(requests.recv().unwrap().contains("authorization: bearer expired"));
+        assert!(requests.recv().unwrap().contains("authorization: bearer fresh"));
+    }
+
+    #[tokio::test]
+    async fn missing_id_is_an_empty_page() {
+        let (url, requests) = serve(vec![response("404 Not Found", r#"{"error": "not found"}"#), response("404 Not Found", "")]);
+        let client = Client::new(url);
+
+        let page = client.get(&table(), Selection::Id(7), Some(10), 0).await.unwrap();
+
+        assert!(page.entries.is_empty());
+        assert_eq!(page.total, Some(0));
+        assert!(requests.recv().unwrap().starts_with("get /api/item/item/7 http/1.1"));
+
+        // only lookups by id, a missing table is still an error
+        assert!(matches!(client.get(&table(), Selection::All, None, 0).await, Err(Error::Response(_))));
+    }
+
+    fn failure(body: &str) -> Failure {
+        Failure {
+            method: reqwest::Method::POST,
+            url: "http://localhost/api/item/item".parse().unwrap(),
+            status: reqwest::StatusCode::BAD_REQUEST,
+            body: body.to_owned(),
+        }
+    }
+
+    #[test]
+    fn failure_reads_error_envelopes() {
+        assert_eq!(failure(r#"{"error": "name taken", "code": 123}"#).message(), "name taken (code 123)");
+        assert_eq!(failure(r#"{"error": {"message": "name taken", "code": "E_DUP"}}"#).message(), "name taken (code E_DUP)");
+        assert_eq!(failure(r#"{"detail": "name taken"}"#).message(), "name taken");
+
+        // anything else is shown as it came
+        for body in ["name taken", r#"{"rows": []}"#, "[1, 2]"] {
+            assert_eq!(failure(body).envelope(), None);
+            assert_eq!(failure(body).message(), body);
+        }
+    }
+
+    #[tokio::test]
+    async fn oversized_body_is_refused() {
+        let body = format!("[\"{}\"]", "x".repeat(64));
+        // announced up front, and only found out while reading
+        let unannounced = format!("HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n{}", body);
+        let (url, _requests) = serve(vec![response("200 OK", &body), unannounced, response("200 OK", &body)]);
+
+        let mut client = Client::new(url);
+        client.max_response = Some(16);
+
+        assert!(matches!(client.tables_raw().await, Err(Error::TooLarge)));
+        assert!(matches!(client.tables_raw().await, Err(Error::TooLarge)));
 
-        Ok(items)
+        client.max_response = None;
+        assert!(client.tables_raw().await.is_ok());
     }
 }
@@ -0,0 +1,174 @@
+use iced::{Element, Font, Length, Task};
+use iced::widget::{button, column, container, row, scrollable, text};
+
+use crate::api;
+use crate::widget;
+
+const SUMMARY_LENGTH: usize = 120;
+
+#[derive(Debug, Clone)]
+pub struct ErrorMessage {
+    pub summary: String,
+    pub raw: Option<String>,
+    // full context for copying into a bug report
+    pub details: String,
+    pub unauthorized: bool,
+    // a request previewed instead of sent, nothing actually failed
+    pub dry_run: bool,
+}
+
+impl ErrorMessage {
+    pub fn new(summary: String) -> Self {
+        Self { details: summary.clone(), summary, raw: None, unauthorized: false, dry_run: false }
+    }
+
+    // keep the first line as a summary, the full body goes behind the expander
+    fn from_response(body: String) -> Self {
+        let line = body.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default();
+
+        let mut summary: String = line.chars().take(SUMMARY_LENGTH).collect();
+
+        if summary.len() < body.trim().len() {
+            summary.push('…');
+
+            Self {
+                raw: Some(body),
+                ..Self::new(format!("error: `{}`", summary))
+            }
+        }
+        else {
+            Self::new(format!("error: `{}`", summary))
+        }
+    }
+}
+
+impl From<api::Error> for ErrorMessage {
+    fn from(err: api::Error) -> Self {
+        let details = err.report();
+        let unauthorized = err.is_unauthorized();
+
+        // the reqwest text stays available behind the expander
+        if let Some(hint) = err.hint() {
+            return Self {
+                raw: Some(details.clone()),
+                details,
+                ..Self::new(hint.to_owned())
+            };
+        }
+
+        match err {
+            // an enveloped message reads well on its own, the json stays behind the expander
+            api::Error::Response(failure) => match failure.envelope() {
+                Some(message) => Self {
+                    raw: Some(failure.body),
+                    details,
+                    unauthorized,
+                    ..Self::new(format!("error: `{}`", message))
+                },
+                None => Self { details, unauthorized, ..Self::from_response(failure.body) },
+            },
+            api::Error::Rejected(columns, failure) => Self {
+                summary: format!("rejected columns: `{}`", columns.join(", ")),
+                raw: Some(failure.body),
+                details,
+                unauthorized,
+                dry_run: false,
+            },
+            api::Error::DryRun(preview) => Self {
+                raw: Some(preview),
+                details,
+                dry_run: true,
+                ..Self::new("dry run, request not sent".to_owned())
+            },
+            err => Self { details, ..Self::new(err.to_string()) },
+        }
+    }
+}
+
+impl From<String> for ErrorMessage {
+    fn from(summary: String) -> Self {
+        Self::new(summary)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MessageBanner {
+    Expand,
+    Copy,
+    Dismiss,
+}
+
+#[derive(Debug)]
+pub struct Banner {
+    error: ErrorMessage,
+    expanded: bool,
+}
+
+impl Banner {
+    pub fn new(error: impl Into<ErrorMessage>) -> Self {
+        Self {
+            error: error.into(),
+            expanded: false,
+        }
+    }
+
+    pub fn view(&self) -> Element<MessageBanner> {
+        let expand = self.error.raw.as_ref().map(|_| {
+            button(text(if self.expanded { "Hide" } else { "Details" }))
+                .on_press(MessageBanner::Expand)
+                .style(button::text)
+        });
+
+        let summary = row![
+            text(self.error.summary.as_str()).style(text::danger).width(Length::Fill),
+        ]
+        .push_maybe(expand)
+        .push(button(text("Copy")).on_press(MessageBanner::Copy).style(button::text))
+        .push(widget::labelled(button(text("×")).on_press(MessageBanner::Dismiss).style(button::text), "Dismiss"))
+        .spacing(8);
+
+        let raw = self.error.raw.as_ref()
+            .filter(|_| self.expanded)
+            .map(|raw| {
+                scrollable(text(raw.as_str()).font(Font::MONOSPACE).size(12))
+                    .width(Length::Fill)
+                    .height(Length::Shrink)
+            });
+
+        let content = column![summary]
+            .push_maybe(raw.map(|raw| container(raw).max_height(240)))
+            .spacing(4);
+
+        container(content)
+            .padding(8)
+            .width(Length::Fill)
+            .style(container::bordered_box)
+            .into()
+    }
+}
+
+pub fn update<T>(banner: &mut Option<Banner>, message: MessageBanner) -> Task<T> {
+    match message {
+        MessageBanner::Expand => {
+            if let Some(banner) = banner {
+                banner.expanded = !banner.expanded;
+            }
+
+            Task::none()
+        },
+        MessageBanner::Copy => {
+            match banner {
+                Some(banner) => iced::clipboard::write(banner.error.details.clone()),
+                None => Task::none(),
+            }
+        },
+        MessageBanner::Dismiss => {
+            *banner = None;
+
+            Task::none()
+        },
+    }
+}
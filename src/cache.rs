@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::table::{ColumnValue, TableDefinition, TableEntry};
+
+// schema and last fetched entries of a backend, kept on disk for working offline
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct Cache {
+    pub tables: Vec<TableDefinition>,
+    entries: HashMap<String, Vec<serde_json::Map<String, Value>>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CacheError {
+    #[error("no cache directory")]
+    NoCacheDir,
+    #[error("io error: `{0}`")]
+    Io(#[from] std::io::Error),
+    #[error("json error: `{0}`")]
+    Json(#[from] serde_json::Error),
+}
+
+impl Cache {
+    pub fn new(tables: Vec<TableDefinition>, entries: &HashMap<String, Vec<TableEntry>>) -> Self {
+        let entries = entries.iter()
+            .map(|(table, entries)| {
+                let entries = entries.iter()
+                    .map(|entry| {
+                        entry.iter()
+                            .map(|(column, value)| (column.clone(), value.clone().map_or(Value::Null, Into::into)))
+                            .collect()
+                    })
+                    .collect();
+
+                (table.clone(), entries)
+            })
+            .collect();
+
+        Self { tables, entries }
+    }
+
+    // values that no longer parse are dropped rather than failing the whole cache
+    pub fn into_parts(self) -> (Vec<TableDefinition>, HashMap<String, Vec<TableEntry>>) {
+        let tables = &self.tables;
+
+        let entries = self.entries.into_iter()
+            .map(|(table, entries)| {
+                let definition = tables.iter().find_map(|definition| definition.get(&table));
+
+                let entries = entries.into_iter()
+                    .map(|entry| {
+                        entry.into_iter()
+                            .filter_map(|(column, value)| {
+                                let ty = definition
+                                    .and_then(|definition| definition.columns.iter().find(|c| c.name == column))
+                                    .map(|column| column.ty);
+
+                                ColumnValue::try_from_value(value, ty).ok().map(|value| (column, value))
+                            })
+                            .collect()
+                    })
+                    .collect();
+
+                (table, entries)
+            })
+            .collect();
+
+        (self.tables, entries)
+    }
+
+    // one file per backend url
+    fn path(url: &str) -> Option<PathBuf> {
+        let name: String = url.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        dirs::cache_dir().map(|dir| dir.join("gameshopui").join(format!("{}.json", name)))
+    }
+
+    pub fn load(url: &str) -> Option<Self> {
+        Self::path(url)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+    }
+
+    // drop the cached data of every backend
+    pub fn clear_all() -> Result<(), CacheError> {
+        let dir = dirs::cache_dir().ok_or(CacheError::NoCacheDir)?.join("gameshopui");
+
+        match std::fs::remove_dir_all(dir) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    // drop the cached data of one backend
+    pub fn clear(url: &str) -> Result<(), CacheError> {
+        let path = Self::path(url).ok_or(CacheError::NoCacheDir)?;
+
+        match std::fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn save(&self, url: &str) -> Result<(), CacheError> {
+        let path = Self::path(url).ok_or(CacheError::NoCacheDir)?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let text = serde_json::to_string(self)?;
+        std::fs::write(path, text)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use std::path::{Path, PathBuf};
+use iced::futures::{SinkExt, Stream};
+use tokio::io::AsyncWriteExt;
+use crate::api::{self, Client, Selection};
+use crate::table::{ColumnValue, Table, TableEntry};
+
+// rows asked for per request while exporting
+const PAGE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl Format {
+    pub const ALL: [Format; 2] = [
+        Format::Csv,
+        Format::Json,
+    ];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+        }
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Format::Csv => "CSV",
+            Format::Json => "JSON",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    // rows written so far, and how many there are when the server says
+    Progress(usize, Option<usize>),
+    Done(usize),
+    Failed(String),
+}
+
+// where an export of `table` goes, the downloads folder when there is one
+pub fn path(table: &Table, format: Format) -> PathBuf {
+    let dir = dirs::download_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_default();
+
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+
+    dir.join(format!("{}-{}.{}", table.table, stamp, format.extension()))
+}
+
+// every row of `table` written to `path` a page at a time, dropping the stream stops it
+pub fn run(client: Client, table: Table, format: Format, path: PathBuf) -> impl Stream<Item = Event> {
+    iced::stream::channel(16, move |mut output| async move {
+        let event = match write(&client, &table, format, &path, &mut output).await {
+            Ok(rows) => Event::Done(rows),
+            Err(err) => Event::Failed(err),
+        };
+
+        let _ = output.send(event).await;
+
+        // the subscription is dropped once the outcome is seen
+        std::future::pending().await
+    })
+}
+
+async fn write(
+    client: &Client,
+    table: &Table,
+    format: Format,
+    path: &Path,
+    output: &mut iced::futures::channel::mpsc::Sender<Event>,
+) -> Result<usize, String> {
+    let file = tokio::fs::File::create(path).await
+        .map_err(|err| format!("{}: {}", path.display(), err))?;
+    let mut file = tokio::io::BufWriter::new(file);
+
+    let columns: Vec<_> = table.columns.iter()
+        .map(|column| column.name.as_str())
+        .collect();
+
+    let head = match format {
+        Format::Csv => csv_line(columns.iter().map(|column| column.to_string())),
+        Format::Json => "[\n".to_owned(),
+    };
+
+    file.write_all(head.as_bytes()).await.map_err(|err| err.to_string())?;
+
+    // without paging the whole table comes in one response
+    let paging = client.supports("paging");
+    let mut written = 0;
+    let mut first: Option<TableEntry> = None;
+
+    loop {
+        let limit = paging.then_some(PAGE);
+
+        let page = client.get(table, Selection::All, limit, written).await
+            .map_err(|err| err.to_string())?;
+
+        let count = page.entries.len();
+
+        // a server that ignores the offset sends the same rows again, forever
+        if count > 0 && written > 0 && page.entries.first() == first.as_ref() {
+            return Err("the server ignored the export's page offset, the file holds only the first page".to_owned());
+        }
+
+        first = page.entries.first().cloned();
+
+        let mut chunk = String::new();
+
+        for entry in page.entries {
+            match format {
+                Format::Csv => chunk.push_str(&csv_line(columns.iter().map(|column| csv_field(entry.get(*column))))),
+                Format::Json => {
+                    if written > 0 || !chunk.is_empty() {
+                        chunk.push_str(",\n");
+                    }
+
+                    chunk.push_str(&json_line(entry));
+                },
+            }
+        }
+
+        file.write_all(chunk.as_bytes()).await.map_err(|err| err.to_string())?;
+
+        written += count;
+
+        let _ = output.send(Event::Progress(written, page.total)).await;
+
+        // a page longer than asked for means the limit was ignored and the whole table came at once
+        if !paging || count != PAGE || page.total.is_some_and(|total| written >= total) {
+            break;
+        }
+    }
+
+    if format == Format::Json {
+        file.write_all(b"\n]\n").await.map_err(|err| err.to_string())?;
+    }
+
+    file.flush().await.map_err(|err| err.to_string())?;
+
+    Ok(written)
+}
+
+// nulls are left empty
+fn csv_field(value: Option<&Option<ColumnValue>>) -> String {
+    match value {
+        Some(Some(value)) => value.to_string(),
+        _ => String::new(),
+    }
+}
+
+// anything with a separator, quote or line break is quoted
+fn csv_escape(value: String) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    }
+    else {
+        value
+    }
+}
+
+fn csv_line(fields: impl Iterator<Item = String>) -> String {
+    let mut line = fields.map(csv_escape).collect::<Vec<_>>().join(",");
+    line.push_str("\r\n");
+
+    line
+}
+
+fn json_line(entry: TableEntry) -> String {
+    format!("  {}", api::body(entry))
+}
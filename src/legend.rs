@@ -0,0 +1,80 @@
+// marks and tints of the entries grid, the cells and the legend both take their symbols from here
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indicator {
+    Optional,
+    Reference,
+    Expand,
+    Edited,
+    Mismatch,
+    Unsaved,
+    Rejected,
+    Null,
+    Duplicate,
+    Added,
+    Changed,
+    Pin,
+    Filter,
+    Wrap,
+    Nulls,
+    CopyColumn,
+}
+
+impl Indicator {
+    pub const ALL: [Indicator; 16] = [
+        Indicator::Optional,
+        Indicator::Reference,
+        Indicator::Expand,
+        Indicator::Edited,
+        Indicator::Mismatch,
+        Indicator::Unsaved,
+        Indicator::Rejected,
+        Indicator::Null,
+        Indicator::Duplicate,
+        Indicator::Added,
+        Indicator::Changed,
+        Indicator::Pin,
+        Indicator::Filter,
+        Indicator::Wrap,
+        Indicator::Nulls,
+        Indicator::CopyColumn,
+    ];
+
+    // what's drawn, tints and text colors are shown on a sample
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Indicator::Optional => "?",
+            Indicator::Reference => "→",
+            Indicator::Expand => "▸",
+            Indicator::Edited => "•",
+            Indicator::Mismatch => "≠",
+            Indicator::Unsaved | Indicator::Rejected | Indicator::Duplicate | Indicator::Added | Indicator::Changed => "abc",
+            Indicator::Null => "null",
+            Indicator::Pin => "⇤",
+            Indicator::Filter => "⏷",
+            Indicator::Wrap => "¶",
+            Indicator::Nulls => "∅",
+            Indicator::CopyColumn => "⧉",
+        }
+    }
+
+    pub fn meaning(&self) -> &'static str {
+        match self {
+            Indicator::Optional => "after a column name, the column may be null",
+            Indicator::Reference => "opens the row the foreign key points to",
+            Indicator::Expand => "shows the referenced row under the value",
+            Indicator::Edited => "changed this session, hover for the value it had",
+            Indicator::Mismatch => "stored as another type than the column declares",
+            Indicator::Unsaved => "edited but not saved yet",
+            Indicator::Rejected => "refused by the server on the last save",
+            Indicator::Null => "no value",
+            Indicator::Duplicate => "the value repeats in the column",
+            Indicator::Added => "row added since the snapshot",
+            Indicator::Changed => "value changed since the snapshot",
+            Indicator::Pin => "pins the column to the left",
+            Indicator::Filter => "filters the column",
+            Indicator::Wrap => "wraps long text",
+            Indicator::Nulls => "how the column's nulls read",
+            Indicator::CopyColumn => "copies the whole column",
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+use iced::futures::{SinkExt, Stream};
+use crate::api::{Change, Client, Error};
+use crate::table::Table;
+
+// wait before reopening a stream that dropped
+const RECONNECT: Duration = Duration::from_secs(5);
+
+// how the change stream of the shown table is doing
+#[derive(Debug, Clone, PartialEq)]
+pub enum Live {
+    Connecting,
+    Streaming,
+    // the server has no changes endpoint, refreshing is all there is
+    Unsupported,
+    Lost(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    Status(Live),
+    Change(Change),
+}
+
+// changes to `table` for as long as it's subscribed, reconnecting after failures
+pub fn changes(client: Client, table: Table) -> impl Stream<Item = Event> {
+    iced::stream::channel(16, move |mut output| async move {
+        loop {
+            let _ = output.send(Event::Status(Live::Connecting)).await;
+
+            let mut stream = match client.changes(&table).await {
+                Ok(stream) => stream,
+                Err(Error::Response(failure)) if failure.status == reqwest::StatusCode::NOT_FOUND => {
+                    let _ = output.send(Event::Status(Live::Unsupported)).await;
+
+                    // nothing to retry, the subscription stays idle until it's dropped
+                    return std::future::pending().await;
+                },
+                Err(err) => {
+                    let _ = output.send(Event::Status(Live::Lost(err.to_string()))).await;
+
+                    tokio::time::sleep(RECONNECT).await;
+
+                    continue;
+                },
+            };
+
+            let _ = output.send(Event::Status(Live::Streaming)).await;
+
+            let lost = loop {
+                match stream.next().await {
+                    Ok(Some(change)) => {
+                        let _ = output.send(Event::Change(change)).await;
+                    },
+                    Ok(None) => break "closed by the server".to_owned(),
+                    Err(err) => break err.to_string(),
+                }
+            };
+
+            let _ = output.send(Event::Status(Live::Lost(lost))).await;
+
+            tokio::time::sleep(RECONNECT).await;
+        }
+    })
+}
@@ -3,37 +3,224 @@
 
 mod table;
 mod api;
+mod settings;
+mod banner;
+mod widget;
+mod cache;
+mod render;
+mod live;
+mod legend;
+mod export;
 
+use std::collections::{HashMap, HashSet};
 use std::iter;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use iced::{Task, Element, Length, Theme};
-use iced::widget::{button, column, container, horizontal_rule, row, scrollable, text, text_input, vertical_rule, Space};
+use iced::{event, keyboard, window, Task, Element, Length, Subscription, Theme};
+use iced::widget::{button, checkbox, column, container, horizontal_rule, mouse_area, pick_list, progress_bar, row, scrollable, text, text_editor, text_input, tooltip, vertical_rule, Space};
 use itertools::Either;
-use table::{TableColumn, Table, TableDefinition, TableEntry};
-use api::{Client, Selection};
+use table::{ColumnParseError, ColumnType, ColumnValue, TableColumn, Table, TableDefinition, TableEntry};
+use api::{Change, Client, Comp, Filter, Operator, Page, Selection};
+use settings::{Bookmark, Environment, EnvironmentColor, MessageSettings, NullCopy, NullDisplay, Settings, TableOrder};
+use banner::{Banner, ErrorMessage, MessageBanner};
+use cache::Cache;
+use render::ColumnRenderer;
+use live::Live;
+use legend::Indicator;
 
 fn main() -> iced::Result {
-    iced::application("gameshopui", State::update, State::view)
-        .theme(State::theme)
+    iced::daemon(App::title, App::update, App::view)
+        .theme(App::theme)
+        .subscription(App::subscription)
         .run_with(|| {
+            let settings = Settings::load();
+
+            let mut client = Client::new("http://127.0.0.1:5000".to_owned());
+            let mut environment = (String::new(), EnvironmentColor::default());
+
+            // otherwise the default url is tried, as it always has been
+            if let Some(target) = settings.launch_target() {
+                client.url = target.url;
+                client.token = target.token;
+                client.prefix = target.prefix;
+                environment = (target.name, target.color);
+            }
+
             let state = StateConnect {
-                client: Client::new("http://127.0.0.1:5000".to_owned()),
+                client,
+                settings,
                 state: RequestState::Idle,
                 message: None,
+                cached: None,
+                resume: None,
+                previous: None,
+                tested: None,
+                closing: None,
+                schema: None,
+                environment: environment.0,
+                environment_color: environment.1,
             };
 
-            let task = state.task_api_tables().map(Message::Connect);
+            let task = state.task_api_tables(false).map(Message::Connect);
 
-            let state = State::Connect(state);
+            let (main, open) = window::open(window_settings());
 
-            (state, task)
+            let app = App {
+                main,
+                state: State::Connect(state),
+                windows: HashMap::new(),
+            };
+
+            // the url can be typed right away
+            let focus = open.discard().chain(text_input::focus(url_input_id()));
+
+            (app, Task::batch([focus, task]))
         })
 }
 
+// closing is confirmed by the app when there are unsaved edits
+fn window_settings() -> window::Settings {
+    window::Settings {
+        exit_on_close_request: false,
+        ..window::Settings::default()
+    }
+}
+
 #[derive(Debug, Clone)]
 enum Message {
     Connect(MessageConnect),
-    View(MessageTable)
+    View(MessageTable),
+    // from a table opened in a window of its own
+    Window(window::Id, MessageTable),
+    CloseRequested(window::Id),
+    Closed(window::Id),
+    Focused(window::Id),
+}
+
+#[derive(Debug)]
+struct App {
+    main: window::Id,
+    state: State,
+    // tables opened in windows of their own, each independent of the main one
+    windows: HashMap<window::Id, StateTable>,
+}
+
+impl App {
+    pub fn title(&self, id: window::Id) -> String {
+        let table = self.windows.get(&id).and_then(|state| {
+            state.get_selected_table().map(|table| state.settings.table_name(table))
+        });
+
+        match table {
+            Some(table) => format!("gameshopui - {}", table),
+            None => "gameshopui".to_owned(),
+        }
+    }
+
+    pub fn theme(&self, _id: window::Id) -> Theme {
+        Theme::Dark
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::View(MessageTable::OpenWindow(table)) => match &self.state {
+                State::View(source) => self.open_window(source.detach(), table),
+                _ => Task::none(),
+            },
+            Message::Window(id, MessageTable::OpenWindow(table)) => match self.windows.get(&id) {
+                Some(source) => self.open_window(source.detach(), table),
+                None => Task::none(),
+            },
+            // there's no connect screen behind a second window, leaving it closes it
+            Message::Window(id, MessageTable::Disconnect) => {
+                self.update(Message::CloseRequested(id))
+            },
+            // switching environments is left to the main window
+            Message::Window(_, MessageTable::Environment(_)) => Task::none(),
+            Message::Window(id, message) => match self.windows.get_mut(&id) {
+                Some(state) => state.update(message).map(move |message| Message::Window(id, message)),
+                None => Task::none(),
+            },
+            Message::CloseRequested(id) if id != self.main => match self.windows.get_mut(&id) {
+                Some(state) if state.has_unsaved_edits() => {
+                    state.closing = Some(id);
+
+                    Task::none()
+                },
+                _ => window::close(id),
+            },
+            // closing the main window quits, taking every other window with it
+            Message::CloseRequested(id) => {
+                let elsewhere = self.windows.values().any(StateTable::has_unsaved_edits);
+
+                self.state.close_requested(id, elsewhere)
+            },
+            Message::Closed(id) if id == self.main => iced::exit(),
+            Message::Closed(id) => {
+                self.windows.remove(&id);
+
+                Task::none()
+            },
+            Message::Focused(id) => {
+                if let State::View(state) = &mut self.state {
+                    state.focused = id == self.main;
+                }
+
+                for (window, state) in &mut self.windows {
+                    state.focused = *window == id;
+                }
+
+                Task::none()
+            },
+            message => self.state.update(message),
+        }
+    }
+
+    fn open_window(&mut self, mut state: StateTable, table: String) -> Task<Message> {
+        let (id, open) = window::open(window_settings());
+
+        let task = state.update(MessageTable::GetRequest(table))
+            .map(move |message| Message::Window(id, message));
+
+        self.windows.insert(id, state);
+
+        Task::batch([open.discard(), task])
+    }
+
+    pub fn view(&self, id: window::Id) -> Element<Message> {
+        if id == self.main {
+            return self.state.view();
+        }
+
+        match self.windows.get(&id) {
+            Some(state) => state.view().map(move |message| Message::Window(id, message)),
+            None => Space::new(Length::Fill, Length::Fill).into(),
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let windows = self.windows.iter().map(|(id, state)| {
+            state.subscription()
+                .with(*id)
+                .map(|(id, message)| Message::Window(id, message))
+        });
+
+        let focus = event::listen_with(|event, _status, id| match event {
+            iced::Event::Window(window::Event::Focused) => Some(Message::Focused(id)),
+            _ => None,
+        });
+
+        Subscription::batch(
+            iter::once(self.state.subscription())
+                .chain(windows)
+                .chain([
+                    window::close_requests().map(Message::CloseRequested),
+                    window::close_events().map(Message::Closed),
+                    focus,
+                ])
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -43,28 +230,153 @@ enum State {
 }
 
 impl State {
-    pub fn theme(&self) -> Theme {
-        Theme::Dark
-    }
-
     pub fn update(&mut self, message: Message) -> iced::Task<Message> {
-        if let Message::Connect(MessageConnect::Response(Ok(tables))) = message {
+        // late responses, e.g. from requests still in flight at a disconnect, have nothing left to update
+        match (&*self, &message) {
+            (State::Connect(_), Message::View(_)) | (State::View(_), Message::Connect(_)) => return Task::none(),
+            _ => (),
+        }
+
+        if let Message::Connect(MessageConnect::Response(latency, Ok(tables))) = message {
+            take_mut::take(self, |state| {
+                let mut state = match state {
+                    State::Connect(state) => state,
+                    _ => unreachable!(),
+                };
+
+                // keep previously cached entries around alongside the fresh schema
+                let cache = Cache::load(&state.client.url)
+                    .map(|cache| cache.into_parts().1)
+                    .unwrap_or_default();
+
+                state.settings.last_environment = state.settings.environment_of(&state.client)
+                    .map(|environment| environment.name.clone());
+
+                // the same backend again, so only the schema is swapped in under the open table
+                let previous = state.previous.take()
+                    .filter(|previous| !previous.offline && previous.client.url == state.client.url);
+
+                if let Some(mut view) = previous {
+                    let message = state.settings.remember_url(&state.client.url)
+                        .err()
+                        .map(|err| Banner::new(err.to_string()));
+
+                    view.client = state.client;
+                    view.settings = state.settings;
+                    view.apply_settings();
+                    view.replace_tables(tables);
+                    view.last_fetch = Some(Fetch::new(latency));
+
+                    if view.message.is_none() {
+                        view.message = message;
+                    }
+
+                    return State::View(*view);
+                }
+
+                let message = state.settings.remember_url(&state.client.url)
+                    .map_err(|err| err.to_string())
+                    .and_then(|_| Cache::new(tables.clone(), &cache).save(&state.client.url).map_err(|err| err.to_string()))
+                    .err()
+                    .map(Banner::new);
+
+                // only offered when reconnecting to the same backend
+                let resume = state.resume.filter(|resume| resume.url == state.client.url);
+
+                let mut state = StateTable::new(state.client, state.settings, tables, cache, false);
+                state.message = message;
+                state.last_fetch = Some(Fetch::new(latency));
+                state.resume = resume;
+
+                State::View(state)
+            });
+
+            match self {
+                State::View(state) => state.task_api_capabilities().map(Message::View),
+                _ => Task::none(),
+            }
+        }
+        else if let Message::Connect(MessageConnect::WorkOffline) = message {
             take_mut::take(self, |state| {
-                let state = match state {
+                let mut state = match state {
                     State::Connect(state) => state,
                     _ => unreachable!(),
                 };
 
-                State::View(StateTable {
-                    client: state.client,
-                    tables,
+                let (tables, cache) = state.cached.take()
+                    .map(Cache::into_parts)
+                    .unwrap_or_default();
+
+                State::View(StateTable::new(state.client, state.settings, tables, cache, true))
+            });
+
+            Task::none()
+        }
+        else if let Message::View(MessageTable::Disconnect) = message {
+            take_mut::take(self, |state| {
+                let mut state = match state {
+                    State::View(state) => state,
+                    _ => unreachable!(),
+                };
+
+                // leaving on purpose, so the next failed connect doesn't offer this backend's old rows,
+                // a kept view forgets them too or it would write them back on reconnecting
+                let message = Cache::clear(&state.client.url).err()
+                    .map(|err| Banner::new(err.to_string()));
+                state.cache.clear();
+
+                // remember what was open so it can be picked up again after reconnecting
+                let resume = state.entries.as_ref().map(|(table, entries)| Resume {
+                    url: state.client.url.clone(),
+                    table: table.clone(),
+                    filter: entries.filter.clone(),
+                });
+
+                let (environment, environment_color) = state.settings.environment_of(&state.client)
+                    .map(|environment| (environment.name.clone(), environment.color))
+                    .unwrap_or_default();
+
+                let client = state.client.clone();
+                let settings = state.settings.clone();
+                let previous = settings.keep_view.then(|| Box::new(state));
+
+                State::Connect(StateConnect {
+                    client,
+                    settings,
                     state: RequestState::Idle,
-                    message: None,
-                    entries: None,
+                    message,
+                    cached: None,
+                    resume,
+                    previous,
+                    tested: None,
+                    closing: None,
+                    schema: None,
+                    environment,
+                    environment_color,
                 })
             });
 
-            Task::none()
+            text_input::focus(url_input_id())
+        }
+        else if let Message::View(MessageTable::Environment(environment)) = message {
+            // the other environment's url never matches, so a kept view wouldn't bring the edits back
+            if let State::View(state) = self {
+                if state.has_unsaved_edits() {
+                    state.message = Some(Banner::new("save or discard the unsaved edits before switching environments".to_owned()));
+
+                    return Task::none();
+                }
+            }
+
+            // switching is a disconnect followed by connecting to the other environment
+            let disconnect = self.update(Message::View(MessageTable::Disconnect));
+
+            let connect = match self {
+                State::Connect(state) => state.update(MessageConnect::Environment(environment)).map(Message::Connect),
+                _ => Task::none(),
+            };
+
+            Task::batch([disconnect, connect])
         }
         else {
             match self {
@@ -88,12 +400,37 @@ impl State {
         }
     }
 
+    // ask before throwing away unsaved edits, including those of a view kept across the disconnect,
+    // `elsewhere` tells of edits in other windows that go along with this one
+    fn close_requested(&mut self, id: window::Id, elsewhere: bool) -> Task<Message> {
+        match self {
+            State::View(state) if elsewhere || state.has_unsaved_edits() => {
+                state.closing = Some(id);
+
+                Task::none()
+            },
+            State::Connect(state) if elsewhere || state.previous.as_ref().is_some_and(|previous| previous.has_unsaved_edits()) => {
+                state.closing = Some(id);
+
+                Task::none()
+            },
+            _ => window::close(id),
+        }
+    }
+
     pub fn view(&self) -> Element<Message> {
         match self {
             State::Connect(state) => state.view().map(Message::Connect),
             State::View(state) => state.view().map(Message::View),
         }
     }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        match self {
+            State::Connect(state) => state.subscription().map(Message::Connect),
+            State::View(state) => state.subscription().map(Message::View),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -102,61 +439,299 @@ enum RequestState {
     Requesting,
 }
 
+// when the last successful request came back and how long it took
+#[derive(Debug, Clone, Copy)]
+struct Fetch {
+    at: chrono::DateTime<chrono::Local>,
+    latency: Duration,
+}
+
+impl Fetch {
+    fn new(latency: Duration) -> Self {
+        Self { at: chrono::Local::now(), latency }
+    }
+}
+
 #[derive(Debug, Clone)]
 enum MessageConnect {
     Edit(String),
+    Token(String),
+    Prefix(String),
+    Environment(Environment),
+    EnvironmentName(String),
+    EnvironmentColor(EnvironmentColor),
+    EnvironmentSave,
+    EnvironmentForget,
+    Forget,
     Connect,
-    Response(Result<Vec<TableDefinition>, String>),
+    Response(Duration, Result<Vec<TableDefinition>, ErrorMessage>),
+    Test,
+    Tested(Result<String, ErrorMessage>),
+    Schema,
+    SchemaAction(text_editor::Action),
+    SchemaClose,
+    CloseCancel,
+    CloseConfirm,
+    WorkOffline,
+    Focus(Move),
+    FocusUrl,
+    Banner(MessageBanner),
 }
 
 #[derive(Debug)]
 struct StateConnect {
     client: Client,
+    settings: Settings,
     state: RequestState,
-    message: Option<String>,
+    message: Option<Banner>,
+    // loaded when the backend can't be reached
+    cached: Option<Cache>,
+    resume: Option<Resume>,
+    // the view left at the last disconnect, taken up again when reconnecting to its backend
+    previous: Option<Box<StateTable>>,
+    // raw schema from the last successful test of the connection
+    tested: Option<String>,
+    // window close held back by unsaved edits of the kept view
+    closing: Option<window::Id>,
+    // raw schema shown without connecting
+    schema: Option<text_editor::Content>,
+    // name and color the current connection is saved under as an environment
+    environment: String,
+    environment_color: EnvironmentColor,
+}
+
+// the table and filter open at the last disconnect
+#[derive(Debug)]
+struct Resume {
+    url: String,
+    table: String,
+    filter: Filter,
 }
 
 impl StateConnect {
     pub fn update(&mut self, message: MessageConnect) -> iced::Task<MessageConnect> {
         match message {
+            MessageConnect::Token(token) => {
+                self.client.token = (!token.is_empty()).then_some(token);
+                self.tested = None;
+
+                Task::none()
+            },
             MessageConnect::Edit(url) => {
                 self.client.url = url;
                 self.message = None;
+                self.cached = None;
+                self.tested = None;
+
+                Task::none()
+            },
+            MessageConnect::Prefix(prefix) => {
+                self.client.prefix = prefix;
+                self.message = None;
+                self.tested = None;
+
+                Task::none()
+            },
+            MessageConnect::Environment(environment) => {
+                self.client.url = environment.url;
+                self.client.token = environment.token;
+                self.client.prefix = environment.prefix;
+                self.environment = environment.name;
+                self.environment_color = environment.color;
+                self.cached = None;
+                self.tested = None;
+
+                self.update(MessageConnect::Connect)
+            },
+            MessageConnect::EnvironmentName(name) => {
+                self.environment = name;
+
+                Task::none()
+            },
+            MessageConnect::EnvironmentColor(color) => {
+                self.environment_color = color;
+
+                Task::none()
+            },
+            MessageConnect::EnvironmentSave => {
+                let url = match Client::normalize_url(&self.client.url) {
+                    Ok(url) => url,
+                    Err(err) => {
+                        self.message = Some(Banner::new(err));
+
+                        return Task::none();
+                    },
+                };
+
+                let environment = Environment {
+                    name: self.environment.trim().to_owned(),
+                    url,
+                    token: self.client.token.clone(),
+                    prefix: self.client.prefix.clone(),
+                    color: self.environment_color,
+                };
+
+                if let Err(err) = self.settings.save_environment(environment) {
+                    self.message = Some(Banner::new(err.to_string()));
+                }
+
+                Task::none()
+            },
+            MessageConnect::EnvironmentForget => {
+                if let Err(err) = self.settings.forget_environment(self.environment.trim()) {
+                    self.message = Some(Banner::new(err.to_string()));
+                }
+
+                Task::none()
+            },
+            MessageConnect::Forget => {
+                if let Err(err) = self.settings.forget_url(&self.client.url) {
+                    self.message = Some(Banner::new(err.to_string()));
+                }
 
                 Task::none()
             },
             MessageConnect::Connect => {
+                match Client::normalize_url(&self.client.url) {
+                    Ok(url) => self.client.url = url,
+                    Err(err) => {
+                        self.message = Some(Banner::new(err));
+
+                        return Task::none();
+                    },
+                }
+
                 self.state = RequestState::Requesting;
                 self.message = None;
 
-                self.task_api_tables()
+                self.task_api_tables(false)
+            },
+            // the schema is fetched but not connected to, so it can be looked at even while the app can't use it
+            MessageConnect::Test => {
+                match Client::normalize_url(&self.client.url) {
+                    Ok(url) => self.client.url = url,
+                    Err(err) => {
+                        self.message = Some(Banner::new(err));
+
+                        return Task::none();
+                    },
+                }
+
+                self.state = RequestState::Requesting;
+                self.message = None;
+
+                self.tested = None;
+
+                self.task_api_tables(true)
+            },
+            MessageConnect::Tested(schema) => {
+                self.state = RequestState::Idle;
+
+                match schema {
+                    Ok(schema) => self.tested = Some(schema),
+                    Err(err) => self.message = Some(Banner::new(err)),
+                }
+
+                Task::none()
+            },
+            MessageConnect::Schema => {
+                self.schema = self.tested.as_deref().map(text_editor::Content::with_text);
+
+                Task::none()
+            },
+            MessageConnect::SchemaAction(action) => {
+                // allow selecting and copying, but not editing
+                if let Some(content) = &mut self.schema {
+                    if !action.is_edit() {
+                        content.perform(action);
+                    }
+                }
+
+                Task::none()
+            },
+            MessageConnect::SchemaClose => {
+                self.schema = None;
+
+                Task::none()
+            },
+            MessageConnect::CloseCancel => {
+                self.closing = None;
+
+                Task::none()
+            },
+            MessageConnect::CloseConfirm => {
+                match self.closing.take() {
+                    Some(id) => window::close(id),
+                    None => Task::none(),
+                }
             },
-            MessageConnect::Response(response) => {
+            MessageConnect::Response(_, response) => {
                 self.state = RequestState::Idle;
 
                 match response {
                     Ok(_) => unreachable!(),
-                    Err(err) => self.message = Some(err),
+                    Err(err) => {
+                        self.cached = Cache::load(&self.client.url);
+                        self.message = Some(Banner::new(err));
+                    },
                 }
 
                 Task::none()
             },
+            MessageConnect::WorkOffline => unreachable!(),
+            MessageConnect::Focus(Move::Previous) => iced::widget::focus_previous(),
+            MessageConnect::Focus(_) => iced::widget::focus_next(),
+            MessageConnect::FocusUrl => {
+                Task::batch([
+                    text_input::focus(url_input_id()),
+                    text_input::select_all(url_input_id()),
+                ])
+            },
+            MessageConnect::Banner(message) => {
+                banner::update(&mut self.message, message)
+            },
         }
     }
 
-    fn task_api_tables(&self) -> Task<MessageConnect> {
+    pub fn subscription(&self) -> Subscription<MessageConnect> {
+        event::listen_with(|event, _status, _window| {
+            let iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event else { return None; };
+
+            match key {
+                keyboard::Key::Named(keyboard::key::Named::Tab) => {
+                    Some(MessageConnect::Focus(if modifiers.shift() { Move::Previous } else { Move::Next }))
+                },
+                // like the address bar of a browser
+                keyboard::Key::Character(key) if key.as_str() == "l" && modifiers.command() => Some(MessageConnect::FocusUrl),
+                _ => None,
+            }
+        })
+    }
+
+    // a test only reports the schema back, otherwise it's parsed and connected to
+    fn task_api_tables(&self, test: bool) -> Task<MessageConnect> {
         let client = self.client.clone();
         let wrapper = || async move {
-            client.tables().await
+            let start = Instant::now();
+            let schema = client.tables_raw().await;
+            (start.elapsed(), schema)
         };
         iced::Task::perform(
             wrapper(),
-            |tables| MessageConnect::Response(tables.map_err(|err| err.to_string())),
+            move |(latency, schema)| {
+                if test {
+                    MessageConnect::Tested(schema.map_err(ErrorMessage::from))
+                }
+                else {
+                    MessageConnect::Response(latency, schema.and_then(|schema| Client::tables_from(&schema)).map_err(ErrorMessage::from))
+                }
+            },
         )
     }
 
     pub fn view(&self) -> Element<MessageConnect> {
         let input = text_input("API URL", &self.client.url)
+            .id(url_input_id())
             .on_input(MessageConnect::Edit)
             .on_submit(MessageConnect::Connect)
             .width(Length::FillPortion(4));
@@ -174,217 +749,5773 @@ impl StateConnect {
         ]
         .width(512);
 
-        let message = text(
-            if let Some(message) = self.message.clone() { message }
-            else { String::new() }
-        )
-        .style(text::danger);
+        let token = text_input("API token (optional)", self.client.token.as_deref().unwrap_or_default())
+            .on_input(MessageConnect::Token)
+            .on_submit(MessageConnect::Connect)
+            .secure(true)
+            .width(512);
+
+        let prefix = text_input("API prefix (optional), e.g. v2", &self.client.prefix)
+            .on_input(MessageConnect::Prefix)
+            .on_submit(MessageConnect::Connect)
+            .width(512);
+
+        let controls = column![controls, token, prefix].spacing(8);
+
+        let idle = matches!(self.state, RequestState::Idle);
+
+        let environments = (!self.settings.environments.is_empty()).then(|| {
+            let selected = self.settings.environment_of(&self.client).cloned();
+
+            pick_list(self.settings.environments.as_slice(), selected, MessageConnect::Environment)
+                .placeholder("Environment")
+                .width(512)
+        });
+
+        let name = self.environment.trim();
+        let known = self.settings.environment(name).is_some();
+
+        let save = row![
+            text_input("Environment name", &self.environment)
+                .on_input(MessageConnect::EnvironmentName)
+                .width(Length::FillPortion(2)),
+            pick_list(EnvironmentColor::ALL, Some(self.environment_color), MessageConnect::EnvironmentColor)
+                .width(Length::FillPortion(1)),
+            iced::widget::button(text(if known { "Update" } else { "Save" }).center())
+                .on_press_maybe((idle && !name.is_empty()).then_some(MessageConnect::EnvironmentSave))
+                .style(iced::widget::button::secondary)
+                .width(Length::FillPortion(1)),
+            iced::widget::button(text("Forget").center())
+                .on_press_maybe(known.then_some(MessageConnect::EnvironmentForget))
+                .style(iced::widget::button::secondary)
+                .width(Length::FillPortion(1)),
+        ]
+        .spacing(4)
+        .width(512);
+
+        let controls = column![]
+            .push_maybe(environments)
+            .push(controls)
+            .push(save)
+            .spacing(8);
+
+        let history = (!self.settings.history.is_empty()).then(|| {
+            let known = self.settings.history.contains(&self.client.url);
+
+            row![
+                pick_list(self.settings.history.as_slice(), known.then(|| self.client.url.clone()), MessageConnect::Edit)
+                    .placeholder("Recent")
+                    .width(Length::FillPortion(4)),
+                iced::widget::button(text("Forget").center())
+                    .on_press_maybe(known.then_some(MessageConnect::Forget))
+                    .style(iced::widget::button::secondary)
+                    .width(Length::FillPortion(1)),
+            ]
+            .width(512)
+        });
+
+        let test = iced::widget::button(text("Test connection").center())
+            .on_press_maybe(idle.then_some(MessageConnect::Test))
+            .style(iced::widget::button::text)
+            .width(512);
+
+        let schema = self.tested.as_ref().map(|_| {
+            iced::widget::button(text("Inspect schema JSON").center())
+                .on_press(MessageConnect::Schema)
+                .style(iced::widget::button::text)
+                .width(512)
+        });
+
+        let offline = self.cached.as_ref().map(|_| {
+            iced::widget::button(text("Work offline with cached data").center())
+                .on_press(MessageConnect::WorkOffline)
+                .style(iced::widget::button::secondary)
+                .width(512)
+        });
+
+        let controls = column![controls]
+            .push_maybe(history)
+            .push(test)
+            .push_maybe(schema)
+            .push_maybe(offline)
+            .spacing(8);
+
+        let message = self.message.as_ref()
+            .map(|message| message.view().map(MessageConnect::Banner));
+
+        let message = column![].push_maybe(message).width(512);
 
         let column = column![
             Space::with_height(Length::Fill),
             container(controls).center_x(Length::Fill),
             container(message).center_x(Length::Fill).height(Length::Fill),
-        ];
+        ]
+        .spacing(8);
 
-        container(column)
-            .center(Length::Fill)
-            .into()
+        let content = container(column)
+            .center(Length::Fill);
+
+        if let Some(schema) = &self.schema {
+            let dialog = column![
+                text("GET api/tables"),
+                text_editor(schema)
+                    .on_action(MessageConnect::SchemaAction)
+                    .font(iced::Font::MONOSPACE)
+                    .height(400),
+                iced::widget::button(text("Close")).on_press(MessageConnect::SchemaClose),
+            ]
+            .spacing(8);
+
+            widget::modal(content, dialog, MessageConnect::SchemaClose)
+        }
+        else if self.closing.is_some() {
+            let confirm = column![
+                text("Quit with unsaved edits?").size(20),
+                text("Edited rows that haven't been saved will be lost."),
+                row![
+                    Space::with_width(Length::Fill),
+                    iced::widget::button(text("Keep editing"))
+                        .on_press(MessageConnect::CloseCancel)
+                        .style(iced::widget::button::secondary),
+                    iced::widget::button(text("Quit without saving"))
+                        .on_press(MessageConnect::CloseConfirm)
+                        .style(iced::widget::button::danger),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12);
+
+            widget::modal(content, confirm, MessageConnect::CloseCancel)
+        }
+        else {
+            content.into()
+        }
     }
 }
 
 #[derive(Debug, Clone)]
 enum MessageTable {
     Entries(MessageEntries),
+    Settings(MessageSettings),
+    ToggleSettings,
+    AutoRefresh(bool),
+    RefreshInterval(String),
+    DryRun(bool),
+    Disconnect,
+    CloseCancel,
+    CloseConfirm,
+    ResetCancel,
+    ResetConfirm,
+    ReauthToken(String),
+    ReauthSubmit,
+    ReauthCancel,
+    TablesRequest,
+    TablesResponse(Duration, Result<Vec<TableDefinition>, ErrorMessage>),
+    CountResponse(String, Result<Option<usize>, ErrorMessage>),
+    CapabilitiesResponse(Result<Option<api::Capabilities>, ErrorMessage>),
     GetRequest(String),
-    GetResponse(String, Result<Vec<TableEntry>, String>),
+    GetResponse(String, Duration, Result<Page, ErrorMessage>),
+    OpenWindow(String),
+    Recent(usize),
+    Resume,
+    Environment(Environment),
+    RenameStart(String),
+    RenameInput(String),
+    RenameSubmit,
+    RenameCancel,
+    BookmarkInput(String),
+    BookmarkSave,
+    BookmarkCancel,
+    BookmarkOpen(String),
+    RevealResponse(String, String, ColumnValue, Duration, Result<Page, ErrorMessage>),
+    Banner(MessageBanner),
 }
 
 #[derive(Debug)]
 struct StateTable {
     client: Client,
+    settings: Settings,
     tables: Vec<TableDefinition>,
     state: RequestState,
-    message: Option<String>,
+    message: Option<Banner>,
     entries: Option<(String, StateEntries)>,
+    cache: HashMap<String, Vec<TableEntry>>,
+    show_settings: bool,
+    auto_refresh: bool,
+    refresh_interval: String,
+    // opened from the on-disk cache, nothing is fetched or saved
+    offline: bool,
+    last_fetch: Option<Fetch>,
+    // window close held back by unsaved edits
+    closing: Option<window::Id>,
+    // reset of all settings waiting for confirmation
+    resetting: bool,
+    reauth: Option<Reauth>,
+    // approximate row counts shown in the sidebar, filled in as they arrive
+    counts: HashMap<String, usize>,
+    // most recently opened tables first
+    recent: Vec<String>,
+    resume: Option<Resume>,
+    // table whose alias is being edited in the sidebar, and the text so far
+    renaming: Option<(String, String)>,
+    // name of the open view being saved as a bookmark, as typed so far
+    bookmarking: Option<String>,
+    // keyboard shortcuts only act in the window that has focus
+    focused: bool,
+}
+
+// a request that failed for lack of a valid token, retried once a new one is entered
+#[derive(Debug)]
+struct Reauth {
+    token: String,
+    retry: MessageTable,
 }
 
 impl StateTable {
-    fn get_selected_table(&self) -> Option<&Table> {
-        let Some(entries) = self.entries.as_ref() else { return None; };
+    fn new(mut client: Client, settings: Settings, tables: Vec<TableDefinition>, cache: HashMap<String, Vec<TableEntry>>, offline: bool) -> Self {
+        client.filter_transport = settings.filter_transport;
+        client.set_max_requests(settings.max_requests);
+        client.log_bodies = settings.log_bodies;
+        client.timeout = settings.request_timeout.map(Duration::from_secs);
+        client.max_response = settings.max_response.map(|megabytes| megabytes.saturating_mul(1 << 20));
 
-        self.tables.iter()
-            .find_map(|table| table.get(&entries.0))
+        Self {
+            client,
+            settings,
+            tables,
+            state: RequestState::Idle,
+            message: None,
+            entries: None,
+            cache,
+            show_settings: false,
+            auto_refresh: false,
+            refresh_interval: "10".to_owned(),
+            offline,
+            last_fetch: None,
+            closing: None,
+            resetting: false,
+            reauth: None,
+            counts: HashMap::new(),
+            recent: Vec::new(),
+            resume: None,
+            renaming: None,
+            bookmarking: None,
+            focused: true,
+        }
     }
 
-    pub fn update(&mut self, message: MessageTable) -> iced::Task<MessageTable> {
-        match message {
-            MessageTable::Entries(message) => {
-                let entries = self.entries.as_mut().unwrap();
+    // the same backend and schema for another window, with nothing opened yet
+    fn detach(&self) -> Self {
+        let mut state = StateTable::new(self.client.clone(), self.settings.clone(), self.tables.clone(), self.cache.clone(), self.offline);
+        state.counts = self.counts.clone();
 
-                let table = self.tables.iter()
-                    .find_map(|table| table.get(&entries.0))
-                    .unwrap();
+        state
+    }
+
+    // recent tables other than the open one, in the order the quick switcher numbers them
+    fn recent_others(&self) -> Vec<&String> {
+        let selected = self.entries.as_ref().map(|entries| &entries.0);
+
+        self.recent.iter()
+            .filter(|table| Some(*table) != selected)
+            .collect()
+    }
+
+    // the message that repeats a request, for responses that were refused for lack of a valid token
+    fn retry_for(message: &MessageTable) -> Option<MessageTable> {
+        match message {
+            MessageTable::TablesResponse(_, Err(err)) if err.unauthorized => {
+                Some(MessageTable::TablesRequest)
+            },
+            MessageTable::GetResponse(table, _, Err(err)) if err.unauthorized => {
+                Some(MessageTable::GetRequest(table.clone()))
+            },
+            MessageTable::RevealResponse(table, column, value, _, Err(err)) if err.unauthorized => {
+                Some(MessageTable::Entries(MessageEntries::Reveal(table.clone(), column.clone(), value.clone())))
+            },
+            MessageTable::Entries(MessageEntries::GetResponse(_, Err(err))) if err.unauthorized => {
+                Some(MessageTable::Entries(MessageEntries::Refetch))
+            },
+            MessageTable::Entries(MessageEntries::UpdateResponse(id, Err((err, _)))) if err.unauthorized => {
+                Some(MessageTable::Entries(MessageEntries::Resave(id.clone())))
+            },
+            MessageTable::Entries(MessageEntries::ToggleResponse(id, column, _, value, Err(err))) if err.unauthorized => {
+                Some(MessageTable::Entries(MessageEntries::Toggle(id.clone(), column.clone(), value.clone())))
+            },
+            MessageTable::Entries(MessageEntries::InsertResponse(Err(err))) if err.unauthorized => {
+                Some(MessageTable::Entries(MessageEntries::InsertSubmit))
+            },
+            MessageTable::Entries(MessageEntries::InlineResponse(_, Err(err))) if err.unauthorized => {
+                Some(MessageTable::Entries(MessageEntries::InlineSubmit))
+            },
+            _ => None,
+        }
+    }
+
+    // a fresh schema in place of the old one, the open table and its rows stay if it still exists
+    fn replace_tables(&mut self, tables: Vec<TableDefinition>) {
+        self.tables = tables;
+
+        // the schema changed under the selection
+        if self.entries.is_some() && self.get_selected_table().is_none() {
+            self.entries = None;
+            self.message = Some(Banner::new("the selected table no longer exists".to_owned()));
+        }
+
+        if let Err(err) = Cache::new(self.tables.clone(), &self.cache).save(&self.client.url) {
+            self.message = Some(Banner::new(err.to_string()));
+        }
+    }
+
+    // settings the client itself acts on
+    fn apply_settings(&mut self) {
+        self.client.filter_transport = self.settings.filter_transport;
+        self.client.set_max_requests(self.settings.max_requests);
+        self.client.log_bodies = self.settings.log_bodies;
+        self.client.timeout = self.settings.request_timeout.map(Duration::from_secs);
+        self.client.max_response = self.settings.max_response.map(|megabytes| megabytes.saturating_mul(1 << 20));
+        self.sync_client();
+    }
+
+    // the entries keep a copy of the client
+    fn sync_client(&mut self) {
+        if let Some((_, entries)) = &mut self.entries {
+            entries.client = self.client.clone();
+        }
+    }
+
+    fn has_unsaved_edits(&self) -> bool {
+        self.entries.as_ref().is_some_and(|(_, entries)| {
+            !entries.pending.is_empty()
+                || entries.editing.is_some()
+                || entries.json_editor.is_some()
+                || entries.insert.is_some()
+        })
+    }
+
+    fn get_selected_table(&self) -> Option<&Table> {
+        let Some(entries) = self.entries.as_ref() else { return None; };
+
+        self.tables.iter()
+            .find_map(|table| table.get(&entries.0))
+    }
+
+    pub fn update(&mut self, message: MessageTable) -> iced::Task<MessageTable> {
+        if let Some(retry) = Self::retry_for(&message) {
+            let token = self.client.token.clone().unwrap_or_default();
+            self.reauth = Some(Reauth { token, retry });
+        }
+
+        match message {
+            MessageTable::Entries(MessageEntries::Reveal(table, column, value)) => {
+                self.reveal(table, column, value)
+            },
+            MessageTable::Entries(MessageEntries::Expand(row)) => {
+                self.expand(row)
+            },
+            MessageTable::Entries(MessageEntries::Settings(message)) => {
+                self.update(MessageTable::Settings(message))
+            },
+            // the name is typed in the sidebar, so it's brought back if hidden
+            MessageTable::Entries(MessageEntries::BookmarkStart) => {
+                self.bookmarking = Some(String::new());
+
+                let expand = if self.settings.sidebar_collapsed {
+                    self.update(MessageTable::Settings(MessageSettings::SidebarCollapsed(false)))
+                }
+                else {
+                    Task::none()
+                };
+
+                Task::batch([expand, text_input::focus(bookmark_input_id())])
+            },
+            MessageTable::Entries(MessageEntries::InsertOpen) => {
+                let Some((name, entries)) = &mut self.entries else { return Task::none(); };
+
+                // families let the user pick which leaf to create
+                let definition = self.tables.iter()
+                    .find(|table| table.get(name).is_some());
+
+                if let Some(definition) = definition {
+                    let leaves = definition.get_leaves().cloned().unwrap_or_default();
+                    let table = definition.get(name).unwrap().clone();
+
+                    entries.open_insert(table, leaves);
+                }
+
+                Task::none()
+            },
+            MessageTable::Entries(message) => {
+                if let MessageEntries::GetResponse(latency, Ok(_)) = &message {
+                    self.last_fetch = Some(Fetch::new(*latency));
+                }
+
+                // the table may have been closed, e.g. dropped from a fresh schema, while a request was in flight
+                let Some(entries) = self.entries.as_mut() else { return Task::none(); };
+
+                let Some(table) = self.tables.iter().find_map(|table| table.get(&entries.0)) else {
+                    return Task::none();
+                };
+
+                let task = entries.1.update(table, message).map(MessageTable::Entries);
+
+                // whatever the request outcome, an offline session stays read-only
+                if self.offline {
+                    entries.1.offline = true;
+                }
+
+                task
+            }
+            MessageTable::Settings(MessageSettings::Reset) => {
+                self.resetting = true;
+
+                Task::none()
+            },
+            MessageTable::ResetCancel => {
+                self.resetting = false;
+
+                Task::none()
+            },
+            MessageTable::ResetConfirm => {
+                self.resetting = false;
+
+                let result = self.settings.reset()
+                    .map_err(|err| err.to_string())
+                    .and_then(|_| Cache::clear_all().map_err(|err| err.to_string()));
+
+                if let Err(err) = result {
+                    self.message = Some(Banner::new(err));
+                }
+
+                self.cache.clear();
+                self.apply_settings();
+
+                Task::none()
+            },
+            MessageTable::Settings(message) => {
+                if let Err(err) = self.settings.update(message) {
+                    self.message = Some(Banner::new(err.to_string()));
+                }
+
+                self.apply_settings();
+
+                Task::none()
+            },
+            MessageTable::RenameStart(table) => {
+                let alias = self.settings.aliases.get(&table).cloned().unwrap_or_default();
+                self.renaming = Some((table, alias));
+
+                text_input::focus(rename_input_id())
+            },
+            MessageTable::RenameInput(alias) => {
+                if let Some(renaming) = &mut self.renaming {
+                    renaming.1 = alias;
+                }
+
+                Task::none()
+            },
+            MessageTable::RenameSubmit => {
+                let Some((table, alias)) = self.renaming.take() else { return Task::none(); };
+
+                self.update(MessageTable::Settings(MessageSettings::Alias(table, alias)))
+            },
+            MessageTable::RenameCancel => {
+                self.renaming = None;
+
+                Task::none()
+            },
+            MessageTable::BookmarkInput(name) => {
+                if let Some(bookmarking) = &mut self.bookmarking {
+                    *bookmarking = name;
+                }
+
+                Task::none()
+            },
+            MessageTable::BookmarkSave => {
+                let Some(name) = self.bookmarking.take() else { return Task::none(); };
+                let Some((table, entries)) = &self.entries else { return Task::none(); };
+
+                let name = match name.trim() {
+                    "" => self.get_selected_table().map_or(table.clone(), |table| self.settings.table_name(table)),
+                    name => name.to_owned(),
+                };
+
+                let bookmark = Bookmark {
+                    name,
+                    table: table.clone(),
+                    filter: entries.filter.clone(),
+                    provenance: entries.provenance,
+                };
+
+                self.update(MessageTable::Settings(MessageSettings::Bookmark(bookmark)))
+            },
+            MessageTable::BookmarkCancel => {
+                self.bookmarking = None;
+
+                Task::none()
+            },
+            MessageTable::BookmarkOpen(name) => {
+                let Some(bookmark) = self.settings.bookmarks.iter().find(|bookmark| bookmark.name == name).cloned() else {
+                    return Task::none();
+                };
+
+                self.open_view(bookmark.table, bookmark.filter, bookmark.provenance)
+            },
+            MessageTable::ToggleSettings => {
+                self.show_settings = !self.show_settings;
+
+                Task::none()
+            },
+            MessageTable::DryRun(enabled) => {
+                self.client.dry_run = enabled;
+                self.sync_client();
+
+                Task::none()
+            },
+            MessageTable::AutoRefresh(enabled) => {
+                self.auto_refresh = enabled;
+
+                Task::none()
+            },
+            MessageTable::RefreshInterval(interval) => {
+                self.refresh_interval = interval;
+
+                Task::none()
+            },
+            MessageTable::TablesRequest => {
+                self.state = RequestState::Requesting;
+                self.message = None;
+
+                self.task_api_tables()
+            },
+            MessageTable::TablesResponse(latency, tables) => {
+                self.state = RequestState::Idle;
+
+                match tables {
+                    Ok(tables) => {
+                        self.last_fetch = Some(Fetch::new(latency));
+                        self.replace_tables(tables);
+
+                        self.task_api_counts()
+                    },
+                    Err(err) => {
+                        self.message = Some(Banner::new(err));
+
+                        Task::none()
+                    },
+                }
+            },
+            // counts are a nicety, failures leave the entry without one
+            MessageTable::CountResponse(table, count) => {
+                if let Ok(Some(count)) = count {
+                    self.counts.insert(table, count);
+                }
+
+                Task::none()
+            },
+            // a failed probe is treated like a backend without one
+            MessageTable::CapabilitiesResponse(capabilities) => {
+                let capabilities = capabilities.ok().flatten();
+
+                if let Some(warning) = capabilities.as_ref().and_then(api::Capabilities::compatibility) {
+                    self.message = Some(Banner::new(warning));
+                }
+
+                self.client.capabilities = capabilities;
+                self.sync_client();
+
+                self.task_api_counts()
+            },
+            MessageTable::GetRequest(table) if self.offline => {
+                self.show_settings = false;
+
+                match self.cache.get(&table) {
+                    Some(entries) => {
+                        self.message = None;
+                        self.entries = Some((table, StateEntries::new(self.client.clone(), entries.clone(), true, self.settings.max_rows)));
+                    },
+                    None => self.message = Some(Banner::new(format!("`{}` is not cached", table))),
+                }
+
+                Task::none()
+            },
+            MessageTable::GetRequest(table) => {
+                let Some(table) = self.tables.iter().find_map(|definition| definition.get(&table)).cloned() else {
+                    self.message = Some(Banner::new(format!("table not found: `{}`", table)));
+
+                    return Task::none();
+                };
+
+                self.state = RequestState::Requesting;
+                self.message = None;
+                self.show_settings = false;
+
+                self.task_api_get(&table, Selection::All)
+            },
+            MessageTable::GetResponse(table, latency, entries) => {
+                self.state = RequestState::Idle;
+
+                if entries.is_ok() {
+                    self.last_fetch = Some(Fetch::new(latency));
+                }
+
+                match entries {
+                    Ok(page) => {
+                        // auto-refresh mostly fetches the same rows again, only changes are worth a write
+                        if self.cache.get(&table) != Some(&page.entries) {
+                            self.cache.insert(table.clone(), page.entries.clone());
+
+                            if let Err(err) = Cache::new(self.tables.clone(), &self.cache).save(&self.client.url) {
+                                self.message = Some(Banner::new(err.to_string()));
+                            }
+                        }
+
+                        let mut entries = StateEntries::new(self.client.clone(), page.entries, self.offline, self.settings.max_rows);
+                        entries.total = page.total;
+
+                        self.recent.retain(|recent| *recent != table);
+                        self.recent.insert(0, table.clone());
+                        self.recent.truncate(RECENT_LENGTH);
+
+                        self.entries = Some((table, entries));
+                    },
+                    Err(err) if err.dry_run => {
+                        self.message = Some(Banner::new(err));
+                    },
+                    Err(err) => {
+                        // fall back to the last successful fetch
+                        if let Some(entries) = self.cache.get(&table) {
+                            self.entries = Some((table, StateEntries::new(self.client.clone(), entries.clone(), true, self.settings.max_rows)));
+                        }
+
+                        self.message = Some(Banner::new(err));
+                    },
+                }
+
+                Task::none()
+            },
+            MessageTable::Resume => {
+                let Some(resume) = self.resume.take() else { return Task::none(); };
+
+                self.open_view(resume.table, resume.filter, false)
+            },
+            MessageTable::Recent(index) => {
+                let Some(table) = self.recent_others().get(index).map(|table| (*table).clone()) else {
+                    return Task::none();
+                };
+
+                if !matches!(self.state, RequestState::Idle) {
+                    return Task::none();
+                }
+
+                self.update(MessageTable::GetRequest(table))
+            },
+            MessageTable::RevealResponse(table, column, value, latency, entries) => {
+                self.state = RequestState::Idle;
+
+                if entries.is_ok() {
+                    self.last_fetch = Some(Fetch::new(latency));
+                }
+
+                match entries {
+                    Ok(page) if page.entries.is_empty() => {
+                        self.message = Some(Banner::new(format!("referenced row not found: `{}` {} == {}", table, column, value)));
+                    },
+                    Ok(page) => {
+                        let mut entries = StateEntries::new(self.client.clone(), page.entries, self.offline, self.settings.max_rows);
+                        entries.total = page.total;
+                        entries.filter.insert(&column, Comp::Eq(value.clone()));
+                        entries.highlight = Some((column, value));
+
+                        self.entries = Some((table, entries));
+                    },
+                    Err(err) => self.message = Some(Banner::new(err)),
+                }
+
+                Task::none()
+            },
+            MessageTable::Disconnect => unreachable!(),
+            MessageTable::Environment(_) => unreachable!(),
+            MessageTable::ReauthToken(token) => {
+                if let Some(reauth) = &mut self.reauth {
+                    reauth.token = token;
+                }
+
+                Task::none()
+            },
+            MessageTable::ReauthSubmit => {
+                let Some(reauth) = self.reauth.take() else { return Task::none(); };
+
+                self.client.token = (!reauth.token.is_empty()).then_some(reauth.token);
+                self.sync_client();
+                self.message = None;
+
+                self.update(reauth.retry)
+            },
+            MessageTable::ReauthCancel => {
+                self.reauth = None;
+
+                Task::none()
+            },
+            // the app owns the windows and opens it
+            MessageTable::OpenWindow(_) => unreachable!(),
+            MessageTable::CloseCancel => {
+                self.closing = None;
+
+                Task::none()
+            },
+            MessageTable::CloseConfirm => {
+                match self.closing.take() {
+                    Some(id) => window::close(id),
+                    None => Task::none(),
+                }
+            },
+            MessageTable::Banner(message) => {
+                banner::update(&mut self.message, message)
+            },
+        }
+    }
+
+    // a table fetched with the filter and view options already in place, for resuming and bookmarks
+    fn open_view(&mut self, table_name: String, filter: Filter, provenance: bool) -> iced::Task<MessageTable> {
+        let Some(table) = self.tables.iter().find_map(|definition| definition.get(&table_name)).cloned() else {
+            self.message = Some(Banner::new(format!("`{}` no longer exists", table_name)));
+
+            return Task::none();
+        };
+
+        self.message = None;
+        self.show_settings = false;
+
+        // the filter has to be in place before the fetch, so the entries are set up empty first
+        let mut entries = StateEntries::new(self.client.clone(), Vec::new(), self.offline, self.settings.max_rows);
+        entries.filter = filter;
+        entries.provenance = provenance;
+
+        let task = entries.task_api_get(&table).map(MessageTable::Entries);
+        self.entries = Some((table_name, entries));
+
+        task
+    }
+
+    // open the table a foreign key points to, narrowed down to the referenced row
+    fn reveal(&mut self, table_name: String, column: String, value: ColumnValue) -> iced::Task<MessageTable> {
+        let Some(table) = self.tables.iter().find_map(|table| table.get(&table_name)).cloned() else {
+            self.message = Some(Banner::new(format!("referenced table not found: `{}`", table_name)));
+
+            return Task::none();
+        };
+
+        let by_id = table.columns.iter()
+            .any(|key| key.primary_key && key.name == column);
+
+        let selection = match value {
+            ColumnValue::Int(id) if by_id => i32::try_from(id).ok().map(Selection::Id),
+            _ => None,
+        };
+
+        let selection = selection.unwrap_or_else(|| {
+            Selection::Filter(Filter::new().eq(&column, value.clone()))
+        });
+
+        self.state = RequestState::Requesting;
+        self.message = None;
+        self.show_settings = false;
+
+        let client = self.client.clone();
+        let target = table.clone();
+        let limit = self.settings.max_rows;
+        let wrapper = || async move {
+            let start = Instant::now();
+            let get = client.get(&target, selection, limit, 0).await;
+            (start.elapsed(), get)
+        };
+
+        iced::Task::perform(
+            wrapper(),
+            move |(latency, get)| MessageTable::RevealResponse(table_name.clone(), column.clone(), value.clone(), latency, get.map_err(ErrorMessage::from)),
+        )
+    }
+
+    // toggle a row open, fetching each row its foreign keys point to unless it's already known
+    fn expand(&mut self, row: usize) -> iced::Task<MessageTable> {
+        let Some((name, entries)) = &mut self.entries else { return Task::none(); };
+
+        if !entries.expanded.insert(row) {
+            entries.expanded.remove(&row);
+
+            return Task::none();
+        }
+
+        let Some(table) = self.tables.iter().find_map(|table| table.get(name)) else { return Task::none(); };
+
+        let tasks: Vec<_> = table.columns.iter()
+            .filter_map(|column| {
+                let key = column.foreign_keys.first()?;
+                let value = entries.value(row, &column.name)?.clone();
+                let related = (key.table.clone(), key.column.clone(), value.to_string());
+
+                if !matches!(entries.related.get(&related), None | Some(Related::Failed(_))) {
+                    return None;
+                }
+
+                let Some(target) = self.tables.iter().find_map(|table| table.get(&key.table)).cloned() else {
+                    entries.related.insert(related, Related::Failed(format!("table `{}` not found", key.table)));
+
+                    return None;
+                };
+
+                if self.offline {
+                    entries.related.insert(related, Related::Failed("offline".to_owned()));
+
+                    return None;
+                }
+
+                entries.related.insert(related.clone(), Related::Loading);
+
+                let by_id = target.columns.iter()
+                    .any(|column| column.primary_key && column.name == key.column);
+
+                let selection = match value {
+                    ColumnValue::Int(id) if by_id => i32::try_from(id).ok().map(Selection::Id),
+                    _ => None,
+                };
+
+                let selection = selection.unwrap_or_else(|| Selection::Filter(Filter::new().eq(&key.column, value)));
+
+                let client = self.client.clone();
+                let wrapper = || async move {
+                    let page = client.get(&target, selection, Some(1), 0).await?;
+
+                    Ok::<_, api::Error>(page.entries.first().map(|entry| StateEntries::related_fields(&target, entry)))
+                };
+
+                let task = iced::Task::perform(
+                    wrapper(),
+                    move |fields| MessageTable::Entries(MessageEntries::RelatedResponse(related.clone(), fields.map_err(ErrorMessage::from))),
+                );
+
+                Some(task)
+            })
+            .collect();
+
+        Task::batch(tasks)
+    }
+
+    fn task_api_get(&self, table: &Table, selection: Selection) -> iced::Task<MessageTable> {
+        let client = self.client.clone();
+        let target = table.clone();
+        let limit = self.settings.max_rows;
+        let wrapper = || async move {
+            let start = Instant::now();
+            let get = client.get(&target, selection, limit, 0).await;
+            (start.elapsed(), get)
+        };
+
+        let table_name = table.table.clone();
+        iced::Task::perform(
+            wrapper(),
+            move |(latency, get)| MessageTable::GetResponse(table_name.clone(), latency, get.map_err(ErrorMessage::from)),
+        )
+    }
+
+    // one request per listed table, all in flight at once
+    fn task_api_counts(&self) -> Task<MessageTable> {
+        if !self.client.supports("count") {
+            return Task::none();
+        }
+
+        let tables: Vec<_> = self.tables.iter()
+            .flat_map(|definition| match definition.get_leaves() {
+                Some(leaves) => leaves.iter().collect(),
+                None => vec![definition.get_base()],
+            })
+            .map(|table| table.table.clone())
+            .collect();
+
+        let tasks = tables.into_iter()
+            .map(|table| {
+                let client = self.client.clone();
+                let table_name = table.clone();
+                let wrapper = || async move { client.count(&table).await };
+
+                iced::Task::perform(
+                    wrapper(),
+                    move |count| MessageTable::CountResponse(table_name.clone(), count.map_err(ErrorMessage::from)),
+                )
+            });
+
+        iced::Task::batch(tasks)
+    }
+
+    fn task_api_capabilities(&self) -> Task<MessageTable> {
+        let client = self.client.clone();
+        let wrapper = || async move { client.capabilities().await };
+
+        iced::Task::perform(
+            wrapper(),
+            |capabilities| MessageTable::CapabilitiesResponse(capabilities.map_err(ErrorMessage::from)),
+        )
+    }
+
+    fn task_api_tables(&self) -> Task<MessageTable> {
+        let client = self.client.clone();
+        let wrapper = || async move {
+            let start = Instant::now();
+            let tables = client.tables().await;
+            (start.elapsed(), tables)
+        };
+        iced::Task::perform(
+            wrapper(),
+            |(latency, tables)| MessageTable::TablesResponse(latency, tables.map_err(ErrorMessage::from)),
+        )
+    }
+
+    pub fn view(&self) -> Element<MessageTable> {
+        let tables = self.sidebar_view();
+
+        let entries = if self.show_settings {
+            self.settings.view().map(MessageTable::Settings)
+        }
+        else if let Some((entries, table)) = self.entries.as_ref().zip(self.get_selected_table()) {
+            column![]
+                .push_maybe(self.recent_view())
+                .push(self.toolbar_view())
+                .push(entries.1.view(table, &self.settings).map(MessageTable::Entries))
+                .into()
+        }
+        else if let Some(resume) = &self.resume {
+            let filters = resume.filter.iter().count();
+
+            let label = match filters {
+                0 => format!("Resume {}", resume.table),
+                1 => format!("Resume {} with 1 filter", resume.table),
+                filters => format!("Resume {} with {} filters", resume.table, filters),
+            };
+
+            let idle = matches!(self.state, RequestState::Idle);
+
+            container(
+                button(text(label))
+                    .on_press_maybe(idle.then_some(MessageTable::Resume))
+            )
+            .center(Length::Fill)
+            .into()
+        }
+        else {
+            Space::new(Length::Fill, Length::Fill).into()
+        };
+
+        let message = self.message.as_ref()
+            .map(|message| message.view().map(MessageTable::Banner));
+
+        let reauth = self.reauth.as_ref().map(|reauth| {
+            container(
+                row![
+                    text("The server refused the token").style(text::danger),
+                    text_input("new token", &reauth.token)
+                        .on_input(MessageTable::ReauthToken)
+                        .on_submit(MessageTable::ReauthSubmit)
+                        .secure(true)
+                        .width(Length::Fill),
+                    button(text("Retry")).on_press(MessageTable::ReauthSubmit),
+                    button(text("Cancel")).on_press(MessageTable::ReauthCancel).style(button::secondary),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center)
+            )
+            .padding(8)
+            .style(container::bordered_box)
+        });
+
+        let entries = column![]
+            .push_maybe(reauth)
+            .push_maybe(message)
+            .push(entries)
+            .width(Length::Fill);
+
+        let content = row![
+            tables,
+            vertical_rule(0),
+            entries,
+        ];
+
+        let content = column![]
+            .push_maybe(self.environment_view())
+            .push(content)
+            .push(horizontal_rule(0))
+            .push(self.status_view());
+
+        if self.closing.is_some() {
+            let confirm = column![
+                text("Quit with unsaved edits?").size(20),
+                text("Edited rows that haven't been saved will be lost."),
+                row![
+                    Space::with_width(Length::Fill),
+                    button(text("Keep editing"))
+                        .on_press(MessageTable::CloseCancel)
+                        .style(button::secondary),
+                    button(text("Quit without saving"))
+                        .on_press(MessageTable::CloseConfirm)
+                        .style(button::danger),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12);
+
+            widget::modal(content, confirm, MessageTable::CloseCancel)
+        }
+        else if self.resetting {
+            let confirm = column![
+                text("Reset all settings?").size(20),
+                text("Display options, pinned tables, connection history and cached data are removed."),
+                row![
+                    Space::with_width(Length::Fill),
+                    button(text("Cancel"))
+                        .on_press(MessageTable::ResetCancel)
+                        .style(button::secondary),
+                    button(text("Reset"))
+                        .on_press(MessageTable::ResetConfirm)
+                        .style(button::danger),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12);
+
+            widget::modal(content, confirm, MessageTable::ResetCancel)
+        }
+        else {
+            content.into()
+        }
+    }
+
+    fn status_view(&self) -> Element<MessageTable> {
+        let fetch = match self.last_fetch {
+            Some(fetch) => format!("updated {} ({} ms)", fetch.at.format("%H:%M:%S"), fetch.latency.as_millis()),
+            None if self.offline => "offline".to_owned(),
+            None => "not updated yet".to_owned(),
+        };
+
+        let rows = self.entries.as_ref()
+            .map(|(_, entries)| {
+                let rows = match entries.total {
+                    Some(total) => format!("{} of {} rows", entries.entries.len(), total),
+                    None => format!("{} rows", entries.entries.len()),
+                };
+
+                text(rows).size(12)
+            });
+
+        let duplicates = self.entries.as_ref()
+            .and_then(|(_, entries)| entries.duplicates.as_ref().zip(entries.duplicate_rows()))
+            .map(|(column, (_, groups))| {
+                let label = match groups {
+                    1 => format!("1 duplicated value in {}", column),
+                    groups => format!("{} duplicated values in {}", groups, column),
+                };
+
+                text(label).size(12).style(if groups > 0 { text::danger } else { text::secondary })
+            });
+
+        let live = self.entries.as_ref()
+            .filter(|_| self.settings.live_updates)
+            .and_then(|(_, entries)| entries.live.as_ref())
+            .map(|live| {
+                let (label, style): (_, fn(&Theme) -> text::Style) = match live {
+                    Live::Connecting => ("live: connecting…".to_owned(), text::secondary),
+                    Live::Streaming => ("live".to_owned(), text::success),
+                    Live::Unsupported => ("live: not offered by the server".to_owned(), text::secondary),
+                    Live::Lost(err) => (format!("live: reconnecting ({})", err), text::danger),
+                };
+
+                text(label).size(12).style(style)
+            });
+
+        let version = self.client.capabilities.as_ref()
+            .and_then(|capabilities| capabilities.version.as_ref())
+            .map(|version| text(format!("api {}", version)).size(12).style(text::secondary));
+
+        row![
+            text(self.client.url.as_str()).size(12).width(Length::Fill),
+        ]
+        .push_maybe(version)
+        .push(text(fetch).size(12))
+        .push_maybe(live)
+        .push_maybe(duplicates)
+        .push_maybe(rows)
+        .spacing(16)
+        .padding([2, 8])
+        .into()
+    }
+
+    fn sidebar_view(&self) -> Element<MessageTable> {
+        if self.settings.sidebar_collapsed {
+            let expand = button(text("»").width(Length::Fill).center())
+                .on_press(MessageTable::Settings(MessageSettings::SidebarCollapsed(false)))
+                .style(button::text)
+                .width(Length::Fill);
+
+            return column![widget::labelled(expand, "Show tables")]
+                .width(32)
+                .into();
+        }
+
+        let mut definitions: Vec<_> = self.tables.iter().collect();
+        if self.settings.table_order == TableOrder::Alphabetical {
+            definitions.sort_by_cached_key(|table| self.settings.table_name(table.get_base()));
+        }
+
+        let (pinned, definitions): (Vec<_>, Vec<_>) = definitions.into_iter()
+            .partition(|table| self.settings.is_pinned(&table.get_base().table));
+
+        let pinned = (!pinned.is_empty()).then(|| {
+            column![text("Pinned").size(12).style(text::secondary)]
+                .extend(self.view_definitions(pinned))
+                .push(horizontal_rule(1))
+        });
+
+        let tables = self.view_definitions(definitions);
+
+        let bookmarks = self.bookmarks_view();
+
+        let settings = button(text("Settings").width(Length::Fill).center())
+            .on_press(MessageTable::ToggleSettings)
+            .style(if self.show_settings { button::primary } else { button::secondary })
+            .width(Length::Fill);
+
+        let idle = matches!(self.state, RequestState::Idle);
+
+        let reload = button(text("Reload tables").width(Length::Fill).center())
+            .on_press_maybe((idle && !self.offline).then_some(MessageTable::TablesRequest))
+            .style(button::secondary)
+            .width(Length::Fill);
+
+        let disconnect = button(text("Disconnect").width(Length::Fill).center())
+            .on_press(MessageTable::Disconnect)
+            .style(button::secondary)
+            .width(Length::Fill);
+
+        let collapse = button(text("«").width(Length::Fill).center())
+            .on_press(MessageTable::Settings(MessageSettings::SidebarCollapsed(true)))
+            .style(button::text)
+            .width(Length::Fill);
+
+        let offline = self.offline.then(|| {
+            text("Offline (read-only)")
+                .style(text::danger)
+                .width(Length::Fill)
+                .center()
+        });
+
+        column![widget::labelled(collapse, "Hide tables")]
+            .push_maybe(offline)
+            .push_maybe(pinned)
+            .push_maybe(bookmarks)
+            .push(column(tables))
+            .push(Space::with_height(Length::Fill))
+            .push(settings)
+            .push(reload)
+            .push(disconnect)
+            .width(256)
+        .into()
+    }
+
+    // saved views of the tables this backend has, and the name of the one being saved
+    fn bookmarks_view(&self) -> Option<Element<MessageTable>> {
+        let idle = matches!(self.state, RequestState::Idle);
+
+        let bookmarks: Vec<Element<MessageTable>> = self.settings.bookmarks.iter()
+            .filter(|bookmark| self.tables.iter().any(|definition| definition.get(&bookmark.table).is_some()))
+            .map(|bookmark| {
+                let filter: Vec<_> = bookmark.filter.iter()
+                    .map(|(column, comp)| format!("{} {}", column, comp))
+                    .collect();
+
+                let tip = if filter.is_empty() {
+                    format!("`{}`", bookmark.table)
+                }
+                else {
+                    format!("`{}` where {}", bookmark.table, filter.join(", "))
+                };
+
+                let open = button(text(bookmark.name.as_str()).width(Length::Fill).center())
+                    .on_press_maybe(idle.then(|| MessageTable::BookmarkOpen(bookmark.name.clone())))
+                    .width(Length::Fill);
+
+                let forget = button(text("×"))
+                    .on_press(MessageTable::Settings(MessageSettings::ForgetBookmark(bookmark.name.clone())))
+                    .style(button::text);
+
+                row![widget::labelled(open, tip), widget::labelled(forget, "Forget this view")]
+                    .spacing(4)
+                    .align_y(iced::Alignment::Center)
+                    .into()
+            })
+            .collect();
+
+        let naming = self.bookmarking.as_ref().map(|name| {
+            let input = text_input("view name", name)
+                .id(bookmark_input_id())
+                .on_input(MessageTable::BookmarkInput)
+                .on_submit(MessageTable::BookmarkSave)
+                .width(Length::Fill);
+
+            let cancel = button(text("×"))
+                .on_press(MessageTable::BookmarkCancel)
+                .style(button::text);
+
+            row![input, widget::labelled(cancel, "Don't save the view")]
+                .spacing(4)
+                .align_y(iced::Alignment::Center)
+        });
+
+        if bookmarks.is_empty() && naming.is_none() {
+            return None;
+        }
+
+        Some(
+            column![text("Views").size(12).style(text::secondary)]
+                .extend(bookmarks)
+                .push_maybe(naming)
+                .push(horizontal_rule(1))
+                .into()
+        )
+    }
+
+    // a strip in the environment's color across the top, with a switcher
+    fn environment_view(&self) -> Option<Element<MessageTable>> {
+        if self.settings.environments.is_empty() {
+            return None;
+        }
+
+        let current = self.settings.environment_of(&self.client);
+        let color = current.map(|environment| environment.color.color());
+
+        let label = match current {
+            Some(environment) => text(environment.name.as_str()),
+            None => text(self.client.url.as_str()),
+        };
+
+        let idle = matches!(self.state, RequestState::Idle);
+
+        let switch = pick_list(self.settings.environments.as_slice(), current.cloned(), MessageTable::Environment)
+            .placeholder("Switch environment")
+            .text_size(12);
+
+        let strip = row![label.size(12), Space::with_width(Length::Fill)]
+            .push_maybe(idle.then_some(switch))
+            .spacing(8)
+            .padding([2, 8])
+            .align_y(iced::Alignment::Center);
+
+        let strip = container(strip)
+            .width(Length::Fill)
+            .style(move |theme: &Theme| container::Style {
+                background: Some(color.unwrap_or(theme.extended_palette().background.strong.color).into()),
+                text_color: color.map(|_| iced::Color::BLACK),
+                ..container::Style::default()
+            });
+
+        Some(strip.into())
+    }
+
+    fn recent_view(&self) -> Option<Element<MessageTable>> {
+        let recent = self.recent_others();
+
+        if recent.is_empty() {
+            return None;
+        }
+
+        let idle = matches!(self.state, RequestState::Idle);
+
+        let buttons = recent.into_iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let label = self.tables.iter()
+                    .find_map(|table| table.get(name))
+                    .map_or_else(|| name.clone(), |table| self.settings.table_name(table));
+
+                let button = button(text(label).size(12))
+                    .on_press_maybe(idle.then_some(MessageTable::Recent(index)))
+                    .style(button::secondary);
+
+                widget::labelled(button, format!("Alt+{}", index + 1))
+            });
+
+        let strip = row![text("Recent").size(12).style(text::secondary)]
+            .extend(buttons)
+            .spacing(4)
+            .padding(4)
+            .align_y(iced::Alignment::Center);
+
+        Some(strip.into())
+    }
+
+    fn toolbar_view(&self) -> Element<MessageTable> {
+        let invalid = self.refresh_seconds().is_none();
+
+        let refresh = row![
+            checkbox("Auto refresh", self.auto_refresh)
+                .on_toggle(MessageTable::AutoRefresh),
+            text_input("seconds", &self.refresh_interval)
+                .on_input(MessageTable::RefreshInterval)
+                .width(64)
+                .style(move |theme: &Theme, status| {
+                    let mut style = text_input::default(theme, status);
+                    if invalid {
+                        style.border.color = theme.palette().danger;
+                    }
+                    style
+                }),
+            text("s"),
+        ]
+        .spacing(4)
+        .align_y(iced::Alignment::Center);
+
+        let dry_run = widget::labelled(
+            checkbox("Dry run", self.client.dry_run).on_toggle(MessageTable::DryRun),
+            "Show requests instead of sending them",
+        );
+
+        let live = widget::labelled(
+            checkbox("Live", self.settings.live_updates)
+                .on_toggle(|enabled| MessageTable::Settings(MessageSettings::LiveUpdates(enabled))),
+            "Follow changes pushed by the server, auto refresh still applies when it has none",
+        );
+
+        row![Space::with_width(Length::Fill)]
+            .push(dry_run)
+            .push(Space::with_width(16))
+            .push(live)
+            .push(refresh)
+            .padding(4)
+            .align_y(iced::Alignment::Center)
+            .into()
+    }
+
+    fn refresh_seconds(&self) -> Option<u64> {
+        self.refresh_interval.trim().parse().ok().filter(|seconds| *seconds > 0)
+    }
+
+    pub fn subscription(&self) -> Subscription<MessageTable> {
+        let Some(entries) = &self.entries else { return Subscription::none(); };
+
+        // an open change stream makes polling redundant
+        let streaming = self.settings.live_updates && entries.1.live == Some(Live::Streaming);
+
+        let refresh = match self.refresh_seconds() {
+            Some(seconds) if self.auto_refresh && !self.offline && !streaming => {
+                iced::time::every(Duration::from_secs(seconds))
+                    .map(|_| MessageTable::Entries(MessageEntries::Refresh))
+            },
+            _ => Subscription::none(),
+        };
+
+        // alt+number jumps to a recent table
+        let recent = event::listen_with(|event, _status, _window| {
+            let iced::Event::Keyboard(keyboard::Event::KeyPressed { key: keyboard::Key::Character(key), modifiers, .. }) = event else { return None; };
+
+            let digit = key.parse::<usize>().ok().filter(|digit| (1..=RECENT_LENGTH).contains(digit))?;
+
+            modifiers.alt().then(|| MessageTable::Recent(digit - 1))
+        });
+
+        // another window has the keyboard
+        let recent = if self.focused { recent } else { Subscription::none() };
+
+        let table = self.tables.iter().find_map(|table| table.get(&entries.0));
+
+        let live = match table {
+            Some(table) if self.settings.live_updates && !self.offline && !self.client.dry_run && self.client.supports("changes") => {
+                // one stream per backend and table, switching either replaces it
+                let id = (self.client.url.clone(), self.client.prefix.clone(), table.table.clone());
+
+                Subscription::run_with_id(id, live::changes(self.client.clone(), table.clone()))
+                    .map(|event| MessageTable::Entries(MessageEntries::Live(event)))
+            },
+            _ => Subscription::none(),
+        };
+
+        // runs for as long as the export is shown unfinished, closing it stops it
+        let export = match (table, &entries.1.export) {
+            (Some(table), Some(Export { format, path: Some(path), done: false, .. })) => {
+                Subscription::run_with_id(path.clone(), export::run(self.client.clone(), table.clone(), *format, path.clone()))
+                    .map(|event| MessageTable::Entries(MessageEntries::Export(event)))
+            },
+            _ => Subscription::none(),
+        };
+
+        Subscription::batch([
+            entries.1.subscription(self.focused).map(MessageTable::Entries),
+            refresh,
+            live,
+            export,
+            recent,
+        ])
+    }
+
+    fn view_definitions<'a>(&'a self, definitions: Vec<&'a TableDefinition>) -> Vec<Element<'a, MessageTable>> {
+        definitions.into_iter()
+            .flat_map(|definition| {
+                let base = &definition.get_base().table;
+
+                match definition {
+                    TableDefinition::Single(table) => {
+                        Either::Left(iter::once(self.view_table(table, base)))
+                    },
+                    TableDefinition::Family { base: _, leaves } => {
+                        let mut leaves: Vec<_> = leaves.iter().collect();
+                        if self.settings.table_order == TableOrder::Alphabetical {
+                            leaves.sort_by_cached_key(|table| self.settings.table_name(table));
+                        }
+
+                        Either::Right(
+                            leaves.into_iter()
+                                .map(move |table| self.view_table(table, base))
+                        )
+                    },
+                }
+            })
+            .collect()
+    }
+
+    // base is the table the pin is stored under, the whole family for leaves
+    fn view_table<'a>(&'a self, table: &'a Table, base: &str) -> Element<'a, MessageTable> {
+        if let Some((_, alias)) = self.renaming.as_ref().filter(|(name, _)| *name == table.table) {
+            let input = text_input(&table.pretty_name(), alias)
+                .id(rename_input_id())
+                .on_input(MessageTable::RenameInput)
+                .on_submit(MessageTable::RenameSubmit)
+                .width(Length::Fill);
+
+            let cancel = button(text("×"))
+                .on_press(MessageTable::RenameCancel)
+                .style(button::text);
+
+            return row![input, widget::labelled(cancel, "Keep the current name")]
+                .spacing(4)
+                .align_y(iced::Alignment::Center)
+                .into();
+        }
+
+        let label = text(self.settings.table_name(table))
+            .width(Length::Fill)
+            .center();
+
+        let idle = matches!(self.state, RequestState::Idle);
+        let selected = self.entries.as_ref()
+            .map_or(false, |entries| entries.0 == table.table);
+
+        let pinned = self.settings.is_pinned(base);
+
+        let count = self.counts.get(&table.table).map(|count| {
+            text(approximate(*count)).size(12).style(text::secondary)
+        });
+
+        let pin = button(text(if pinned { "★" } else { "☆" }))
+            .on_press(MessageTable::Settings(MessageSettings::Pin(base.to_owned(), !pinned)))
+            .style(button::text);
+
+        let rename = button(text("✎"))
+            .on_press(MessageTable::RenameStart(table.table.clone()))
+            .style(button::text);
+
+        let detach = button(text("↗"))
+            .on_press(MessageTable::OpenWindow(table.table.clone()))
+            .style(button::text);
+
+        row![
+            button(label)
+                .on_press_maybe((idle && !selected).then_some(MessageTable::GetRequest(table.table.clone())))
+                .width(Length::Fill),
+        ]
+        .push_maybe(count)
+        .push(widget::labelled(detach, "Open in a new window"))
+        .push(widget::labelled(rename, format!("Rename `{}`, blank to reset", table.table)))
+        .push(widget::labelled(pin, if pinned { "Unpin" } else { "Pin to top" }))
+        .spacing(4)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+}
+
+// tables kept in the quick switcher
+const RECENT_LENGTH: usize = 5;
+
+// short row count for the sidebar, e.g. 12.3k
+fn approximate(count: usize) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", count as f64 / 1e3),
+        _ => format!("{:.1}M", count as f64 / 1e6),
+    }
+}
+
+// how long a copied cell says so
+const COPIED_FEEDBACK: Duration = Duration::from_millis(1200);
+
+// tables this wide get a column mini-map
+const MINIMAP_COLUMNS: usize = 12;
+// rows looked at when estimating column widths
+const WIDTH_SAMPLE: usize = 200;
+// width of the save/discard column
+const ACTIONS_WIDTH: f32 = 120.0;
+// width of the column of "+" buttons that add a row in place
+const GUTTER_WIDTH: f32 = 24.0;
+// width of a vertical scrollbar, the header is padded by it to scroll as far as the grid
+const SCROLLBAR_WIDTH: f32 = 10.0;
+
+// width of columns that wrap their text
+const WRAP_WIDTH: f32 = 240.0;
+// columns of a referenced row shown when expanding a foreign key
+const RELATED_FIELDS: usize = 3;
+// width of the input of the cell being edited
+const CELL_EDIT_WIDTH: f32 = 160.0;
+// keys of removed rows listed when comparing to a snapshot
+const DIFF_REMOVED: usize = 10;
+// tint of cells that changed since the snapshot
+const DIFF_CHANGED: iced::Color = iced::Color::from_rgba(0.95, 0.77, 0.06, 0.35);
+// default relative line height of text
+const LINE_HEIGHT: f32 = 1.3;
+
+fn cell_input_id() -> text_input::Id {
+    text_input::Id::new("cell")
+}
+
+fn entries_scrollable_id() -> scrollable::Id {
+    scrollable::Id::new("entries")
+}
+
+fn url_input_id() -> text_input::Id {
+    text_input::Id::new("url")
+}
+
+fn bookmark_input_id() -> text_input::Id {
+    text_input::Id::new("bookmark")
+}
+
+fn rename_input_id() -> text_input::Id {
+    text_input::Id::new("rename")
+}
+
+fn header_scrollable_id() -> scrollable::Id {
+    scrollable::Id::new("header")
+}
+
+fn pinned_scrollable_id() -> scrollable::Id {
+    scrollable::Id::new("pinned")
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Move {
+    Next,
+    Previous,
+    Down,
+}
+
+#[derive(Debug, Clone)]
+enum MessageEntries {
+    FilterBool(String, Option<bool>),
+    FilterRemove(String),
+    FilterColumn(String),
+    FilterOperator(Operator),
+    FilterOperand(String),
+    FilterAdd,
+    FilterPick(usize, String),
+    FilterChoose(String),
+    CopyMode(bool),
+    Transpose(Option<usize>),
+    Provenance(bool),
+    Legend(bool),
+    Snapshot,
+    SnapshotCompare(bool),
+    SnapshotClear,
+    CopyCell(usize, String),
+    CopyColumn(String, NullCopy),
+    CopyTick,
+    HeaderFilterOpen(String),
+    HeaderFilterOperator(Operator),
+    HeaderFilterOperand(String),
+    HeaderFilterApply,
+    HeaderFilterClear,
+    HeaderFilterCancel,
+    QueryOpen,
+    QueryToggle(usize, bool),
+    QueryOperator(usize, Operator),
+    QueryOperand(usize, String),
+    QueryApply,
+    QueryCancel,
+    GetResponse(Duration, Result<Page, ErrorMessage>),
+    MoreResponse(Result<Page, ErrorMessage>),
+    EditStart(usize, String),
+    EditInput(String),
+    EditToggle(Option<bool>),
+    // primary key, column, the value before and the value sent
+    ToggleResponse(ColumnValue, String, Option<ColumnValue>, Option<ColumnValue>, Result<(), ErrorMessage>),
+    // primary key, column and the value to send
+    Toggle(ColumnValue, String, Option<ColumnValue>),
+    EditChoose(String),
+    EditStep(i8),
+    EditNow,
+    TypeHintDismiss,
+    EditMove(Move),
+    EditCancel,
+    Escape,
+    SaveRow(usize),
+    SaveConfirm(usize),
+    // a save tried again, the row is found by its key as it may have moved meanwhile
+    Resave(ColumnValue),
+    SaveCancel,
+    DiscardRow(usize),
+    UpdateResponse(ColumnValue, Result<(), (ErrorMessage, Vec<String>)>),
+    Scrolled(scrollable::Viewport),
+    PinnedScrolled(scrollable::Viewport),
+    ScrollToColumn(f32),
+    ScrollTo(f32),
+    BulkColumn(String),
+    BulkValue(String),
+    BulkConfirm,
+    BulkCancel,
+    BulkApply,
+    BulkStop,
+    ExportOpen,
+    ExportFormat(export::Format),
+    ExportStart,
+    ExportCancel,
+    Export(export::Event),
+    BulkResponse(ColumnValue, Result<(), ErrorMessage>),
+    Reveal(String, String, ColumnValue),
+    Expand(usize),
+    Duplicates(Option<String>),
+    RelatedResponse(RelatedKey, Result<Option<Vec<(String, String)>>, ErrorMessage>),
+    Settings(MessageSettings),
+    Refresh,
+    Refetch,
+    RemoveLimit,
+    JsonAction(text_editor::Action),
+    JsonSave,
+    JsonCancel,
+    Inspect(usize),
+    InspectAction(text_editor::Action),
+    InspectClose,
+    InsertOpen,
+    BookmarkStart,
+    InsertLeaf(String),
+    InsertInput(String, String),
+    InsertSubmit,
+    InsertCancel,
+    InsertResponse(Result<(), ErrorMessage>),
+    InlineOpen(usize),
+    InlineInput(String, String),
+    InlineSubmit,
+    InlineCancel,
+    InlineResponse(TableEntry, Result<(), ErrorMessage>),
+    Live(live::Event),
+    Banner(MessageBanner),
+}
+
+#[derive(Debug)]
+struct CellEdit {
+    row: usize,
+    column: String,
+    value: String,
+    invalid: bool,
+}
+
+#[derive(Debug)]
+struct JsonEdit {
+    row: usize,
+    column: String,
+    content: text_editor::Content,
+    error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct PendingRow {
+    values: TableEntry,
+    rejected: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct FilterDraft {
+    column: Option<String>,
+    operator: Operator,
+    operand: String,
+    error: Option<String>,
+}
+
+// one line per column of the query builder, prefilled from the active filter
+#[derive(Debug)]
+struct QueryCondition {
+    column: String,
+    enabled: bool,
+    operator: Operator,
+    operand: String,
+}
+
+// filter of a single column, edited in place under its header
+#[derive(Debug)]
+struct HeaderFilter {
+    column: String,
+    operator: Operator,
+    operand: String,
+    error: Option<String>,
+}
+
+// rows as they were fetched at some point, by primary key, to tell what changed since
+#[derive(Debug)]
+struct Snapshot {
+    rows: HashMap<(ColumnType, String), TableEntry>,
+    // the grid is tinted by its differences to the snapshot
+    comparing: bool,
+}
+
+// loaded rows against a snapshot
+#[derive(Debug, Default)]
+struct Diff {
+    added: HashSet<usize>,
+    // row -> columns whose value differs
+    changed: HashMap<usize, HashSet<String>>,
+    // keys of snapshot rows that are no longer loaded
+    removed: Vec<String>,
+}
+
+// what every column of the grid looks up per row, worked out once per view
+struct GridRows {
+    heights: Option<Vec<f32>>,
+    duplicates: Option<HashSet<usize>>,
+    diff: Option<Diff>,
+}
+
+#[derive(Debug)]
+struct QueryBuilder {
+    conditions: Vec<QueryCondition>,
+    error: Option<String>,
+}
+
+// text of each field of a new row, in column order
+#[derive(Debug)]
+struct InsertDraft {
+    // the concrete table the row is created in, one of `leaves` for families
+    target: Table,
+    leaves: Vec<Table>,
+    fields: Vec<(String, String)>,
+    // every field that didn't validate, with why
+    problems: Vec<(String, String)>,
+    error: Option<String>,
+}
+
+// a new row typed straight into the grid, shown before the row at `position`
+#[derive(Debug)]
+struct InlineInsert {
+    position: usize,
+    fields: Vec<(String, String)>,
+    problems: Vec<(String, String)>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct BulkDraft {
+    column: Option<String>,
+    value: String,
+    error: Option<String>,
+    confirm: bool,
+}
+
+#[derive(Debug)]
+struct BulkProgress {
+    column: String,
+    value: Option<ColumnValue>,
+    // keys rather than positions, live changes may move rows while it runs
+    rows: Vec<ColumnValue>,
+    done: usize,
+    failures: Vec<String>,
+    // no further rows are sent once the one in flight returns
+    stopped: bool,
+}
+
+// the whole table written to a file a page at a time, none of it kept in the grid
+#[derive(Debug)]
+struct Export {
+    format: export::Format,
+    // where the rows go, none until it's started
+    path: Option<PathBuf>,
+    rows: usize,
+    total: Option<usize>,
+    done: bool,
+}
+
+// referenced table, key column and key value of a foreign key cell
+type RelatedKey = (String, String, String);
+
+// the row a foreign key points to, fetched the first time a row referencing it is expanded
+#[derive(Debug)]
+enum Related {
+    Loading,
+    // a few columns that tell the row apart, as column and text
+    Found(Vec<(String, String)>),
+    Missing,
+    Failed(String),
+}
+
+impl Related {
+    fn lines(&self) -> usize {
+        match self {
+            Related::Found(fields) => fields.len().max(1),
+            _ => 1,
+        }
+    }
+
+    fn texts(&self) -> Vec<String> {
+        match self {
+            Related::Loading => vec!["loading…".to_owned()],
+            Related::Found(fields) if fields.is_empty() => vec!["(no other columns)".to_owned()],
+            Related::Found(fields) => fields.iter().map(|(column, value)| format!("{}: {}", column, value)).collect(),
+            Related::Missing => vec!["not found".to_owned()],
+            Related::Failed(err) => vec![err.clone()],
+        }
+    }
+}
+
+#[derive(Debug)]
+struct StateEntries {
+    client: Client,
+    entries: Vec<TableEntry>,
+    state: RequestState,
+    message: Option<Banner>,
+    filter: Filter,
+    draft: FilterDraft,
+    editing: Option<CellEdit>,
+    pending: HashMap<usize, PendingRow>,
+    offline: bool,
+    scroll_x: f32,
+    scroll_y: f32,
+    // horizontal offset, visible width and content width of the grid as of the last scroll
+    scroll_span: Option<(f32, f32, f32)>,
+    bulk_draft: BulkDraft,
+    bulk: Option<BulkProgress>,
+    export: Option<Export>,
+    highlight: Option<(String, ColumnValue)>,
+    json_editor: Option<JsonEdit>,
+    inspect: Option<text_editor::Content>,
+    limit: Option<usize>,
+    // rows matching overall, when the server reports it
+    total: Option<usize>,
+    // the last page came back full, so there are probably more rows
+    more: bool,
+    loading_more: bool,
+    insert: Option<InsertDraft>,
+    inline: Option<InlineInsert>,
+    // row whose changes are shown for review before saving
+    save_preview: Option<usize>,
+    query: Option<QueryBuilder>,
+    header_filter: Option<HeaderFilter>,
+    // clicking a cell copies it instead of editing
+    copy_mode: bool,
+    // row shown on its own with one line per column, instead of the grid
+    transpose: Option<usize>,
+    // only the key and audit columns are shown
+    provenance: bool,
+    snapshot: Option<Snapshot>,
+    // what the grid's marks and tints mean, for those that can show up in this table
+    legend: bool,
+    // the cell copied last, whether it was empty, and when
+    copied: Option<(usize, String, bool, Instant)>,
+    // the column copied last, how many lines it gave, and when
+    column_copied: Option<(String, usize, Instant)>,
+    // a value was accepted but its type may not be what was meant
+    type_hint: Option<String>,
+    // column whose repeated values are tinted
+    duplicates: Option<String>,
+    // rows showing the rows their foreign keys point to
+    expanded: HashSet<usize>,
+    related: HashMap<RelatedKey, Related>,
+    // state of the change stream, none until it's been subscribed to
+    live: Option<Live>,
+    // value of each cell edited this session as it was first fetched
+    edited: HashMap<(usize, String), Option<ColumnValue>>,
+}
+
+impl StateEntries {
+    pub fn new(client: Client, entries: Vec<TableEntry>, offline: bool, limit: Option<usize>) -> Self {
+        let more = limit.is_some_and(|limit| entries.len() >= limit);
+
+        Self {
+            client,
+            entries,
+            state: RequestState::Idle,
+            message: None,
+            filter: Filter::new(),
+            draft: FilterDraft::default(),
+            editing: None,
+            pending: HashMap::new(),
+            offline,
+            scroll_x: 0.0,
+            scroll_y: 0.0,
+            scroll_span: None,
+            bulk_draft: BulkDraft::default(),
+            bulk: None,
+            export: None,
+            highlight: None,
+            json_editor: None,
+            inspect: None,
+            more,
+            loading_more: false,
+            limit,
+            total: None,
+            insert: None,
+            inline: None,
+            save_preview: None,
+            query: None,
+            header_filter: None,
+            copy_mode: false,
+            transpose: None,
+            provenance: false,
+            snapshot: None,
+            legend: false,
+            copied: None,
+            column_copied: None,
+            type_hint: None,
+            duplicates: None,
+            expanded: HashSet::new(),
+            related: HashMap::new(),
+            live: None,
+            edited: HashMap::new(),
+        }
+    }
+
+    // the server had more rows than it sent, or at least as many as we asked for
+    fn truncated(&self) -> bool {
+        match self.total {
+            Some(total) => total > self.entries.len(),
+            None => self.more,
+        }
+    }
+
+    // a fetch replaces the rows, and with them the edits not saved yet
+    fn refuse_refetch(&mut self) -> bool {
+        if self.pending.is_empty() {
+            return false;
+        }
+
+        self.message = Some(Banner::new("save or discard the unsaved edits before changing which rows are shown".to_owned()));
+
+        true
+    }
+
+    pub fn update(&mut self, table: &Table, message: MessageEntries) -> iced::Task<MessageEntries> {
+        match message {
+            MessageEntries::FilterBool(column, value) => {
+                if self.refuse_refetch() {
+                    return Task::none();
+                }
+
+                match value {
+                    Some(value) => self.filter.insert(&column, Comp::Eq(value.into())),
+                    None => self.filter.remove(&column),
+                }
+
+                self.task_api_get(table)
+            },
+            MessageEntries::FilterRemove(column) => {
+                if self.refuse_refetch() {
+                    return Task::none();
+                }
+
+                self.filter.remove(&column);
+
+                self.task_api_get(table)
+            },
+            MessageEntries::CopyMode(enabled) => {
+                self.copy_mode = enabled;
+
+                Task::none()
+            },
+            MessageEntries::Provenance(enabled) => {
+                self.provenance = enabled;
+
+                Task::none()
+            },
+            MessageEntries::Legend(shown) => {
+                self.legend = shown;
+
+                Task::none()
+            },
+            MessageEntries::Snapshot => {
+                let rows = self.entries.iter()
+                    .filter_map(|entry| Some((Self::key_of(table, entry)?, entry.clone())))
+                    .collect();
+
+                self.snapshot = Some(Snapshot { rows, comparing: false });
+
+                Task::none()
+            },
+            // comparing fetches the rows again, to compare what's there now
+            MessageEntries::SnapshotCompare(comparing) => {
+                let Some(snapshot) = &mut self.snapshot else { return Task::none(); };
+                snapshot.comparing = comparing;
+
+                if comparing { self.update(table, MessageEntries::Refresh) } else { Task::none() }
+            },
+            MessageEntries::SnapshotClear => {
+                self.snapshot = None;
+
+                Task::none()
+            },
+            MessageEntries::Transpose(row) => {
+                self.transpose = row.filter(|row| *row < self.entries.len());
+
+                Task::none()
+            },
+            // the raw value rather than the formatted one, nulls copy as nothing
+            MessageEntries::CopyCell(row, column) => {
+                let value = self.value(row, &column).map(ToString::to_string);
+                let empty = value.is_none();
+
+                self.copied = Some((row, column, empty, Instant::now()));
+
+                iced::clipboard::write(value.unwrap_or_default())
+            },
+            // every loaded row in the order shown, one value per line
+            MessageEntries::CopyColumn(column, nulls) => {
+                let lines: Vec<_> = (0..self.entries.len())
+                    .filter_map(|row| match (self.value(row, &column), nulls) {
+                        (Some(value), _) => Some(value.to_string()),
+                        (None, NullCopy::EmptyLine) => Some(String::new()),
+                        (None, NullCopy::Skip) => None,
+                    })
+                    .collect();
+
+                self.column_copied = Some((column, lines.len(), Instant::now()));
+
+                iced::clipboard::write(lines.join("\n"))
+            },
+            MessageEntries::CopyTick => {
+                if self.copied.as_ref().is_some_and(|copied| copied.3.elapsed() >= COPIED_FEEDBACK) {
+                    self.copied = None;
+                }
+
+                if self.column_copied.as_ref().is_some_and(|copied| copied.2.elapsed() >= COPIED_FEEDBACK) {
+                    self.column_copied = None;
+                }
+
+                Task::none()
+            },
+            // prefilled from the column's active filter, if any
+            MessageEntries::HeaderFilterOpen(column) => {
+                if self.header_filter.as_ref().is_some_and(|filter| filter.column == column) {
+                    self.header_filter = None;
+
+                    return Task::none();
+                }
+
+                let (operator, values) = self.filter.get(&column)
+                    .map(Comp::split)
+                    .unwrap_or_default();
+
+                let operand = values.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                self.header_filter = Some(HeaderFilter { column, operator, operand, error: None });
+
+                Task::none()
+            },
+            MessageEntries::HeaderFilterOperator(operator) => {
+                if let Some(filter) = &mut self.header_filter {
+                    filter.operator = operator;
+                    filter.error = None;
+                }
+
+                Task::none()
+            },
+            MessageEntries::HeaderFilterOperand(operand) => {
+                if let Some(filter) = &mut self.header_filter {
+                    filter.operand = operand;
+                    filter.error = None;
+                }
+
+                Task::none()
+            },
+            MessageEntries::HeaderFilterApply => {
+                if !matches!(self.state, RequestState::Idle) || self.refuse_refetch() {
+                    return Task::none();
+                }
+
+                let Some(filter) = &mut self.header_filter else { return Task::none(); };
+                let Some(column) = table.columns.iter().find(|column| column.name == filter.column) else { return Task::none(); };
+
+                match Self::parse_comp(column, filter.operator, &filter.operand) {
+                    Ok(comp) => {
+                        self.filter.insert(&column.name, comp);
+                        self.header_filter = None;
+
+                        self.task_api_get(table)
+                    },
+                    Err(err) => {
+                        filter.error = Some(err);
+
+                        Task::none()
+                    },
+                }
+            },
+            MessageEntries::HeaderFilterClear => {
+                if self.refuse_refetch() {
+                    return Task::none();
+                }
+
+                let Some(filter) = self.header_filter.take() else { return Task::none(); };
+
+                if self.filter.get(&filter.column).is_none() {
+                    return Task::none();
+                }
+
+                self.filter.remove(&filter.column);
+
+                self.task_api_get(table)
+            },
+            MessageEntries::HeaderFilterCancel => {
+                self.header_filter = None;
+
+                Task::none()
+            },
+            MessageEntries::QueryOpen => {
+                let conditions = table.columns.iter()
+                    .filter(|column| table.polymorphic.as_ref() != Some(&column.name))
+                    .map(|column| {
+                        let (operator, values) = self.filter.get(&column.name)
+                            .map(Comp::split)
+                            .unwrap_or_default();
+
+                        let operand = values.iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ");
+
+                        QueryCondition {
+                            column: column.name.clone(),
+                            enabled: self.filter.get(&column.name).is_some(),
+                            operator,
+                            operand,
+                        }
+                    })
+                    .collect();
+
+                self.query = Some(QueryBuilder { conditions, error: None });
+
+                Task::none()
+            },
+            MessageEntries::QueryToggle(index, enabled) => {
+                if let Some(condition) = self.query.as_mut().and_then(|query| query.conditions.get_mut(index)) {
+                    condition.enabled = enabled;
+                }
+
+                Task::none()
+            },
+            MessageEntries::QueryOperator(index, operator) => {
+                if let Some(condition) = self.query.as_mut().and_then(|query| query.conditions.get_mut(index)) {
+                    condition.operator = operator;
+                    condition.enabled = true;
+                }
+
+                Task::none()
+            },
+            // typing a value switches the condition on
+            MessageEntries::QueryOperand(index, operand) => {
+                if let Some(condition) = self.query.as_mut().and_then(|query| query.conditions.get_mut(index)) {
+                    condition.enabled = true;
+                    condition.operand = operand;
+                }
+
+                Task::none()
+            },
+            MessageEntries::QueryApply => {
+                if !matches!(self.state, RequestState::Idle) || self.refuse_refetch() {
+                    return Task::none();
+                }
+
+                let Some(query) = &mut self.query else { return Task::none(); };
+
+                match Self::query_filter(table, query) {
+                    Ok(filter) => {
+                        self.query = None;
+                        self.filter = filter;
+
+                        self.task_api_get(table)
+                    },
+                    Err(err) => {
+                        query.error = Some(err);
+
+                        Task::none()
+                    },
+                }
+            },
+            MessageEntries::QueryCancel => {
+                self.query = None;
+
+                Task::none()
+            },
+            MessageEntries::FilterColumn(column) => {
+                self.draft.column = Some(column);
+                self.draft.error = None;
+
+                Task::none()
+            },
+            MessageEntries::FilterOperator(operator) => {
+                self.draft.operator = operator;
+                self.draft.error = None;
+
+                Task::none()
+            },
+            MessageEntries::FilterOperand(operand) => {
+                self.draft.operand = operand;
+                self.draft.error = None;
+
+                Task::none()
+            },
+            MessageEntries::FilterPick(row, column) => {
+                // nulls can't be matched by a set
+                let Some(value) = self.value(row, &column) else { return Task::none(); };
+                let value = value.to_string();
+
+                self.add_operand(value);
+
+                Task::none()
+            },
+            MessageEntries::FilterChoose(value) => {
+                if self.draft.operator.arity().is_none() {
+                    self.add_operand(value);
+                }
+                else {
+                    self.draft.operand = value;
+                    self.draft.error = None;
+                }
+
+                Task::none()
+            },
+            MessageEntries::FilterAdd => {
+                if !matches!(self.state, RequestState::Idle) || self.refuse_refetch() {
+                    return Task::none();
+                }
+
+                match self.draft_comp(table) {
+                    Ok((column, comp)) => {
+                        self.filter.insert(&column, comp);
+                        self.draft.operand.clear();
+
+                        self.task_api_get(table)
+                    },
+                    Err(err) => {
+                        self.draft.error = Some(err);
+
+                        Task::none()
+                    },
+                }
+            },
+            MessageEntries::GetResponse(_, entries) => {
+                self.state = RequestState::Idle;
+
+                match entries {
+                    Ok(page) => {
+                        self.more = self.limit.is_some_and(|limit| page.entries.len() >= limit);
+                        self.loading_more = false;
+                        self.entries = page.entries;
+                        self.total = page.total;
+                        self.editing = None;
+                        self.pending.clear();
+                        self.edited.clear();
+                        self.expanded.clear();
+                        self.offline = false;
+
+                        if let Some(inline) = &mut self.inline {
+                            inline.position = inline.position.min(self.entries.len());
+                        }
+
+                        // a revealed row takes the place of whichever one was transposed
+                        self.transpose = self.transpose
+                            .map(|row| self.highlighted_row().unwrap_or(row))
+                            .filter(|row| *row < self.entries.len());
+
+                        // keep a revealed row in view
+                        if let Some(row) = self.highlighted_row() {
+                            let y = row as f32 / self.entries.len().saturating_sub(1).max(1) as f32;
+
+                            return scrollable::snap_to(entries_scrollable_id(), scrollable::RelativeOffset { x: self.scroll_x, y });
+                        }
+                    },
+                    // a preview leaves the entries as they are
+                    Err(err) if err.dry_run => {
+                        self.message = Some(Banner::new(err));
+                    },
+                    Err(err) => {
+                        // keep showing the previous entries, but read-only
+                        self.editing = None;
+                        self.inline = None;
+                        self.offline = true;
+                        self.message = Some(Banner::new(err));
+                    },
+                }
+
+                Task::none()
+            },
+            MessageEntries::EditStart(row, column) => {
+                if self.offline {
+                    return Task::none();
+                }
+
+                if self.commit(table).is_err() {
+                    return Task::none();
+                }
+
+                self.edit(table, row, column)
+            },
+            MessageEntries::EditInput(value) => {
+                if let Some(edit) = &mut self.editing {
+                    edit.value = Self::masked(table, &edit.column, value);
+                    edit.invalid = false;
+                }
+
+                Task::none()
+            },
+            // flipping a flag is the whole edit, it's saved on its own without waiting for the row
+            MessageEntries::EditToggle(checked) => {
+                let Some(edit) = self.editing.take() else { return Task::none(); };
+
+                self.toggle(table, edit.row, edit.column, checked.map(ColumnValue::Bool))
+            },
+            // the same flag set again once a new token is entered
+            MessageEntries::Toggle(id, column, value) => {
+                let row = self.entries.iter().position(|entry| Self::primary_key(table, entry).as_ref() == Some(&id));
+
+                match row {
+                    Some(row) => self.toggle(table, row, column, value),
+                    None => Task::none(),
+                }
+            },
+            // the optimistic value is rolled back unless something newer replaced it meanwhile,
+            // the row is looked up by its key as inserts and refetches may have moved it
+            MessageEntries::ToggleResponse(id, column, previous, value, response) => {
+                let Err(err) = response else { return Task::none(); };
+
+                if let Some(row) = self.row_by_key(table, &id) {
+                    if self.entries[row].get(&column) == Some(&value) {
+                        self.entries[row].insert(column.clone(), previous.clone());
+                    }
+
+                    if self.edited.get(&(row, column.clone())) == Some(&previous) {
+                        self.edited.remove(&(row, column));
+                    }
+                }
+
+                self.message = Some(Banner::new(err));
+
+                Task::none()
+            },
+            // a choice from a dropdown is complete, so it's committed right away
+            MessageEntries::EditChoose(value) => {
+                if let Some(edit) = &mut self.editing {
+                    edit.value = value;
+                }
+
+                let _ = self.commit(table);
+
+                Task::none()
+            },
+            // the spinner of number cells, leaves text that doesn't parse alone
+            MessageEntries::EditStep(step) => {
+                let Some(edit) = &mut self.editing else { return Task::none(); };
+                let Some(column) = table.columns.iter().find(|column| column.name == edit.column) else { return Task::none(); };
+
+                let value = match column.ty {
+                    ColumnType::Int => edit.value.trim().parse::<i64>().ok().map(|value| value.saturating_add(step.into()).to_string()),
+                    ColumnType::Float => edit.value.trim().parse::<f64>().ok().map(|value| (value + f64::from(step)).to_string()),
+                    _ => None,
+                };
+
+                // an empty cell starts counting from zero
+                let value = value.or_else(|| edit.value.trim().is_empty().then(|| step.to_string()));
+
+                if let Some(value) = value {
+                    edit.value = value;
+                    edit.invalid = false;
+                }
+
+                Task::none()
+            },
+            MessageEntries::TypeHintDismiss => {
+                self.type_hint = None;
+
+                Task::none()
+            },
+            MessageEntries::EditNow => {
+                let Some(edit) = &mut self.editing else { return Task::none(); };
+                let column = table.columns.iter().find(|column| column.name == edit.column);
+
+                if let Some(now) = column.and_then(ColumnValue::now_for) {
+                    edit.value = now.to_string();
+                    edit.invalid = false;
+                }
+
+                Task::none()
+            },
+            MessageEntries::EditMove(direction) => {
+                // outside of a cell edit tab moves between inputs
+                let Some(edit) = &self.editing else {
+                    return match direction {
+                        Move::Next => iced::widget::focus_next(),
+                        Move::Previous => iced::widget::focus_previous(),
+                        Move::Down => Task::none(),
+                    };
+                };
+                let (row, column) = (edit.row, edit.column.clone());
+
+                // stay on the cell if its value doesn't parse
+                if self.commit(table).is_err() {
+                    return Task::none();
+                }
+
+                // json cells open an editor of their own, so they're skipped
+                let columns: Vec<_> = table.columns.iter()
+                    .filter(|column| Self::is_editable(table, column) && column.ty != ColumnType::Json)
+                    .map(|column| column.name.clone())
+                    .collect();
+
+                if columns.is_empty() {
+                    return Task::none();
+                }
+
+                let index = columns.iter()
+                    .position(|name| *name == column)
+                    .unwrap_or(0);
+
+                let last_row = self.entries.len().saturating_sub(1);
+
+                let next = match direction {
+                    Move::Next if index + 1 < columns.len() => Some((row, index + 1)),
+                    Move::Next if row < last_row => Some((row + 1, 0)),
+                    Move::Previous if index > 0 => Some((row, index - 1)),
+                    Move::Previous if row > 0 => Some((row - 1, columns.len() - 1)),
+                    Move::Down if row < last_row => Some((row + 1, index)),
+                    _ => None,
+                };
+
+                match next {
+                    Some((row, index)) => self.edit(table, row, columns[index].clone()),
+                    None => Task::none(),
+                }
+            },
+            MessageEntries::EditCancel => {
+                self.editing = None;
+
+                Task::none()
+            },
+            // backs out of an edit first, then out of the filter, dialogs keep their own buttons
+            MessageEntries::Escape => {
+                if self.editing.is_some() {
+                    return self.update(table, MessageEntries::EditCancel);
+                }
+
+                if self.inline.is_some() {
+                    return self.update(table, MessageEntries::InlineCancel);
+                }
+
+                if self.header_filter.is_some() {
+                    return self.update(table, MessageEntries::HeaderFilterCancel);
+                }
+
+                let dialog = self.bulk_draft.confirm
+                    || self.insert.is_some()
+                    || self.save_preview.is_some()
+                    || self.query.is_some()
+                    || self.json_editor.is_some()
+                    || self.inspect.is_some();
+
+                // clearing the filter fetches again, dropping unsaved edits
+                if dialog || !self.pending.is_empty() || self.filter.is_empty() || !matches!(self.state, RequestState::Idle) {
+                    return Task::none();
+                }
+
+                self.filter = Filter::new();
+
+                self.task_api_get(table)
+            },
+            MessageEntries::SaveRow(row) => {
+                if self.commit(table).is_err() {
+                    return Task::none();
+                }
+
+                if self.pending.contains_key(&row) {
+                    self.save_preview = Some(row);
+                }
+
+                Task::none()
+            },
+            MessageEntries::SaveCancel => {
+                self.save_preview = None;
+
+                Task::none()
+            },
+            MessageEntries::SaveConfirm(row) => {
+                self.save_preview = None;
+
+                let values = self.changes(table, row);
+                let Some(id) = self.entries.get(row).and_then(|entry| Self::primary_key(table, entry)) else { return Task::none(); };
+
+                if values.is_empty() {
+                    self.pending.remove(&row);
+
+                    return Task::none();
+                }
+
+                self.state = RequestState::Requesting;
+                self.message = None;
+
+                let key = id.clone();
+                let client = self.client.clone();
+                let table = table.clone();
+                let wrapper = || async move {
+                    client.update(&table, id, values).await
+                };
+
+                iced::Task::perform(
+                    wrapper(),
+                    move |update| {
+                        let update = update.map_err(|err| {
+                            let rejected = match &err {
+                                api::Error::Rejected(columns, _) => columns.clone(),
+                                api::Error::Lossy(column, ..) => vec![column.clone()],
+                                _ => Vec::new(),
+                            };
+
+                            (ErrorMessage::from(err), rejected)
+                        });
+
+                        MessageEntries::UpdateResponse(key.clone(), update)
+                    },
+                )
+            },
+            MessageEntries::Resave(id) => match self.row_by_key(table, &id) {
+                Some(row) => self.update(table, MessageEntries::SaveConfirm(row)),
+                None => Task::none(),
+            },
+            MessageEntries::DiscardRow(row) => {
+                self.pending.remove(&row);
+
+                // only saved changes are worth remembering now
+                let entries = &self.entries;
+                self.edited.retain(|(edited, column), original| {
+                    *edited != row || entries.get(row).and_then(|entry| entry.get(column)).cloned().flatten() != *original
+                });
+
+                if self.editing.as_ref().is_some_and(|edit| edit.row == row) {
+                    self.editing = None;
+                }
+
+                Task::none()
+            },
+            // the row is looked up by its key, live changes may have moved it while the save was out
+            MessageEntries::UpdateResponse(id, response) => {
+                self.state = RequestState::Idle;
+
+                let row = self.row_by_key(table, &id);
+
+                match response {
+                    Ok(()) => {
+                        if let Some(pending) = row.and_then(|row| self.pending.remove(&row)) {
+                            if let Some(entry) = row.and_then(|row| self.entries.get_mut(row)) {
+                                entry.extend(pending.values);
+                            }
+                        }
+                    },
+                    Err((err, rejected)) => {
+                        if let Some(pending) = row.and_then(|row| self.pending.get_mut(&row)) {
+                            pending.rejected = rejected;
+                        }
+
+                        self.message = Some(Banner::new(err));
+                    },
+                }
+
+                Task::none()
+            },
+            MessageEntries::Scrolled(viewport) => {
+                self.scroll_x = viewport.relative_offset().x;
+                self.scroll_y = viewport.relative_offset().y;
+                self.scroll_span = Some((viewport.absolute_offset().x, viewport.bounds().width, viewport.content_bounds().width));
+
+                // the header sits outside the grid, so it follows it sideways, and the pinned columns follow it down
+                let header = Task::batch([
+                    scrollable::scroll_to(header_scrollable_id(), scrollable::AbsoluteOffset { x: viewport.absolute_offset().x, y: 0.0 }),
+                    scrollable::scroll_to(pinned_scrollable_id(), scrollable::AbsoluteOffset { x: 0.0, y: viewport.absolute_offset().y }),
+                ]);
+
+                // fetch the next page once the bottom is close
+                let near_bottom = viewport.relative_offset().y > 0.95;
+                let idle = matches!(self.state, RequestState::Idle) && !self.offline;
+
+                // backends without paging would send the first page again
+                let paging = self.client.supports("paging");
+
+                if near_bottom && idle && paging && self.more && self.truncated() && !self.loading_more {
+                    return Task::batch([header, self.task_api_more(table)]);
+                }
+
+                header
+            },
+            // scrolling over the pinned columns takes the grid along
+            MessageEntries::PinnedScrolled(viewport) => {
+                let x = self.scroll_span.map_or(0.0, |(x, _, _)| x);
+
+                scrollable::scroll_to(entries_scrollable_id(), scrollable::AbsoluteOffset { x, y: viewport.absolute_offset().y })
+            },
+            MessageEntries::MoreResponse(page) => {
+                // the table was fetched again in the meantime
+                if !self.loading_more {
+                    return Task::none();
+                }
+
+                self.loading_more = false;
+
+                match page {
+                    Ok(page) => {
+                        self.more = self.limit.is_some_and(|limit| page.entries.len() >= limit);
+                        self.total = page.total.or(self.total);
+                        self.entries.extend(page.entries);
+                    },
+                    Err(err) => {
+                        // stop trying until the table is fetched again
+                        self.more = false;
+                        self.message = Some(Banner::new(err));
+                    },
+                }
+
+                Task::none()
+            },
+            // `start` is where the column begins, as a fraction of the estimated grid width
+            MessageEntries::ScrollToColumn(start) => {
+                let x = match self.scroll_span {
+                    Some((_, width, content)) if content > width => start * content / (content - width),
+                    _ => start,
+                };
+
+                scrollable::snap_to(entries_scrollable_id(), scrollable::RelativeOffset { x: x.clamp(0.0, 1.0), y: self.scroll_y })
+            },
+            MessageEntries::ScrollTo(y) => {
+                scrollable::snap_to(entries_scrollable_id(), scrollable::RelativeOffset { x: self.scroll_x, y })
+            },
+            MessageEntries::BulkColumn(column) => {
+                self.bulk_draft.column = Some(column);
+                self.bulk_draft.error = None;
+
+                Task::none()
+            },
+            MessageEntries::BulkValue(value) => {
+                self.bulk_draft.value = value;
+                self.bulk_draft.error = None;
+
+                Task::none()
+            },
+            MessageEntries::BulkConfirm => {
+                match self.bulk_value(table).and_then(|_| self.bulk_rows(table)) {
+                    Ok(_) => self.bulk_draft.confirm = true,
+                    Err(err) => self.bulk_draft.error = Some(err),
+                }
+
+                Task::none()
+            },
+            MessageEntries::BulkCancel => {
+                self.bulk_draft.confirm = false;
+
+                Task::none()
+            },
+            MessageEntries::BulkApply => {
+                self.bulk_draft.confirm = false;
+
+                let Ok((column, value)) = self.bulk_value(table) else { return Task::none(); };
+                let Ok(rows) = self.bulk_rows(table) else { return Task::none(); };
+
+                self.state = RequestState::Requesting;
+                self.message = None;
+                self.bulk = Some(BulkProgress {
+                    column,
+                    value,
+                    rows,
+                    done: 0,
+                    failures: Vec::new(),
+                    stopped: false,
+                });
+
+                self.task_bulk_next(table)
+            },
+            MessageEntries::BulkStop => {
+                if let Some(bulk) = &mut self.bulk {
+                    bulk.stopped = true;
+                }
+
+                Task::none()
+            },
+            MessageEntries::ExportOpen => {
+                self.export = Some(Export {
+                    format: export::Format::Csv,
+                    path: None,
+                    rows: 0,
+                    total: None,
+                    done: false,
+                });
+
+                Task::none()
+            },
+            MessageEntries::ExportFormat(format) => {
+                if let Some(export) = self.export.as_mut().filter(|export| export.path.is_none()) {
+                    export.format = format;
+                }
+
+                Task::none()
+            },
+            // the subscription picks it up from here
+            MessageEntries::ExportStart => {
+                if let Some(export) = self.export.as_mut().filter(|export| export.path.is_none()) {
+                    export.path = Some(export::path(table, export.format));
+                }
+
+                Task::none()
+            },
+            // dropping the subscription stops the export, a half written file is no use
+            MessageEntries::ExportCancel => {
+                let export = self.export.take();
+
+                if let Some(path) = export.filter(|export| !export.done).and_then(|export| export.path) {
+                    let _ = std::fs::remove_file(path);
+                }
+
+                Task::none()
+            },
+            MessageEntries::Export(event) => {
+                let Some(export) = &mut self.export else { return Task::none(); };
+
+                match event {
+                    export::Event::Progress(rows, total) => {
+                        export.rows = rows;
+                        export.total = total.or(export.total);
+                    },
+                    export::Event::Done(rows) => {
+                        export.rows = rows;
+                        export.done = true;
+                    },
+                    export::Event::Failed(err) => {
+                        if let Some(path) = self.export.take().and_then(|export| export.path) {
+                            let _ = std::fs::remove_file(path);
+                        }
+
+                        self.message = Some(Banner::new(format!("export: {}", err)));
+                    },
+                }
+
+                Task::none()
+            },
+            MessageEntries::BulkResponse(id, response) => {
+                let row = self.row_by_key(table, &id);
+                let Some(bulk) = &mut self.bulk else { return Task::none(); };
+
+                match response {
+                    Ok(()) => {
+                        let entry = row.and_then(|row| self.entries.get_mut(row));
+
+                        if let (Some(row), Some(entry)) = (row, entry) {
+                            let original = entry.get(&bulk.column).cloned().flatten();
+                            self.edited.entry((row, bulk.column.clone())).or_insert(original);
+
+                            entry.insert(bulk.column.clone(), bulk.value.clone());
+                        }
+                    },
+                    Err(err) => {
+                        bulk.failures.push(format!("{}: {}", id, err.summary));
+                    },
+                }
+
+                bulk.done += 1;
+
+                self.task_bulk_next(table)
+            },
+            MessageEntries::Refresh => {
+                // don't clobber requests in flight or unsaved edits
+                let busy = !matches!(self.state, RequestState::Idle)
+                    || self.editing.is_some()
+                    || !self.pending.is_empty()
+                    || self.bulk_draft.confirm
+                    || self.insert.is_some();
+
+                if busy {
+                    return Task::none();
+                }
+
+                self.task_api_get(table)
+            },
+            MessageEntries::Refetch => self.task_api_get(table),
+            MessageEntries::RemoveLimit => {
+                if self.refuse_refetch() {
+                    return Task::none();
+                }
+
+                self.limit = None;
+
+                self.task_api_get(table)
+            },
+            MessageEntries::JsonAction(action) => {
+                if let Some(editor) = &mut self.json_editor {
+                    editor.content.perform(action);
+                    editor.error = None;
+                }
+
+                Task::none()
+            },
+            MessageEntries::JsonSave => {
+                let Some(editor) = &mut self.json_editor else { return Task::none(); };
+
+                let text = editor.content.text();
+                let value = if text.trim().is_empty() {
+                    None
+                }
+                else {
+                    match serde_json::from_str(&text) {
+                        Ok(value) => Some(ColumnValue::Json(value)),
+                        Err(err) => {
+                            editor.error = Some(err.to_string());
+
+                            return Task::none();
+                        },
+                    }
+                };
+
+                let optional = table.columns.iter()
+                    .any(|column| column.name == editor.column && column.optional);
+
+                if value.is_none() && !optional {
+                    editor.error = Some("value is required".to_owned());
+
+                    return Task::none();
+                }
+
+                let editor = self.json_editor.take().unwrap();
+                self.set_pending(editor.row, editor.column, value);
+
+                Task::none()
+            },
+            MessageEntries::JsonCancel => {
+                self.json_editor = None;
+
+                Task::none()
+            },
+            MessageEntries::Inspect(row) => {
+                let text = table.columns.iter()
+                    .map(|column| {
+                        let value = self.value(row, &column.name)
+                            .map_or(String::new(), |value| value.to_string());
+
+                        format!("{}: {}", column.name, value)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                self.inspect = Some(text_editor::Content::with_text(&text));
+
+                Task::none()
+            },
+            MessageEntries::InspectAction(action) => {
+                // allow selecting and copying, but not editing
+                if let Some(content) = &mut self.inspect {
+                    if !action.is_edit() {
+                        content.perform(action);
+                    }
+                }
+
+                Task::none()
+            },
+            MessageEntries::InspectClose => {
+                self.inspect = None;
+
+                Task::none()
+            },
+            MessageEntries::InsertOpen => unreachable!(),
+            MessageEntries::BookmarkStart => unreachable!(),
+            MessageEntries::InsertLeaf(name) => {
+                let Some(insert) = self.insert.take() else { return Task::none(); };
+
+                let target = insert.leaves.iter()
+                    .find(|leaf| leaf.table == name)
+                    .cloned()
+                    .unwrap_or(insert.target);
+
+                // carry over what was typed into columns both tables share
+                let fields = Self::insert_fields(&target)
+                    .into_iter()
+                    .map(|(column, default)| {
+                        let value = insert.fields.iter()
+                            .find(|(name, _)| name == &column)
+                            .map_or(default, |(_, value)| value.clone());
+
+                        (column, value)
+                    })
+                    .collect();
+
+                self.insert = Some(InsertDraft { target, leaves: insert.leaves, fields, problems: Vec::new(), error: None });
+
+                Task::none()
+            },
+            MessageEntries::InsertInput(column, value) => {
+                if let Some(insert) = &mut self.insert {
+                    let value = Self::masked(&insert.target, &column, value);
+
+                    if let Some(field) = insert.fields.iter_mut().find(|(name, _)| name == &column) {
+                        field.1 = value;
+                    }
+                    insert.problems.retain(|(name, _)| name != &column);
+                    insert.error = None;
+                }
+
+                Task::none()
+            },
+            MessageEntries::InsertSubmit => {
+                if !matches!(self.state, RequestState::Idle) {
+                    return Task::none();
+                }
+
+                let Some(validated) = self.insert.as_ref().map(|insert| self.validate_insert(table, &insert.target, &insert.fields)) else { return Task::none(); };
+                let Some(insert) = &mut self.insert else { return Task::none(); };
+
+                let target = &insert.target;
+
+                let values = match validated {
+                    Ok(values) => values,
+                    Err(problems) => {
+                        insert.problems = problems;
+                        insert.error = None;
+
+                        return Task::none();
+                    },
+                };
+
+                self.state = RequestState::Requesting;
+
+                let client = self.client.clone();
+                let target = target.clone();
+                let wrapper = || async move {
+                    client.insert(&target, values).await
+                };
+
+                iced::Task::perform(
+                    wrapper(),
+                    |insert| MessageEntries::InsertResponse(insert.map_err(ErrorMessage::from)),
+                )
+            },
+            MessageEntries::InsertCancel => {
+                self.insert = None;
+
+                Task::none()
+            },
+            MessageEntries::InsertResponse(response) => {
+                self.state = RequestState::Idle;
+
+                match response {
+                    Ok(()) => {
+                        self.insert = None;
+
+                        // the new row shows with the next fetch, which would take the unsaved edits along
+                        if !self.pending.is_empty() {
+                            self.message = Some(Banner::new("row added, refresh after saving the edits to see it".to_owned()));
+
+                            return Task::none();
+                        }
+
+                        self.task_api_get(table)
+                    },
+                    Err(err) => {
+                        if let Some(insert) = &mut self.insert {
+                            insert.error = Some(err.summary);
+                        }
+
+                        Task::none()
+                    },
+                }
+            },
+            MessageEntries::InlineOpen(position) => {
+                if self.offline || self.bulk.is_some() || table.columns.is_empty() {
+                    return Task::none();
+                }
+
+                self.editing = None;
+                self.inline = Some(InlineInsert {
+                    position: position.min(self.entries.len()),
+                    fields: Self::insert_fields(table),
+                    problems: Vec::new(),
+                    error: None,
+                });
+
+                Task::none()
+            },
+            MessageEntries::InlineInput(column, value) => {
+                if let Some(inline) = &mut self.inline {
+                    let value = Self::masked(table, &column, value);
+
+                    if let Some(field) = inline.fields.iter_mut().find(|(name, _)| name == &column) {
+                        field.1 = value;
+                    }
+                    inline.problems.retain(|(name, _)| name != &column);
+                    inline.error = None;
+                }
+
+                Task::none()
+            },
+            MessageEntries::InlineSubmit => {
+                if !matches!(self.state, RequestState::Idle) {
+                    return Task::none();
+                }
+
+                let Some(validated) = self.inline.as_ref().map(|inline| self.validate_insert(table, table, &inline.fields)) else { return Task::none(); };
+                let Some(inline) = &mut self.inline else { return Task::none(); };
+
+                let values = match validated {
+                    Ok(values) => values,
+                    Err(problems) => {
+                        inline.problems = problems;
+                        inline.error = None;
+
+                        return Task::none();
+                    },
+                };
+
+                self.state = RequestState::Requesting;
+
+                let client = self.client.clone();
+                let table = table.clone();
+                let sent = values.clone();
+                let wrapper = || async move {
+                    client.insert(&table, values).await
+                };
+
+                iced::Task::perform(
+                    wrapper(),
+                    move |insert| MessageEntries::InlineResponse(sent.clone(), insert.map_err(ErrorMessage::from)),
+                )
+            },
+            MessageEntries::InlineCancel => {
+                self.inline = None;
+
+                Task::none()
+            },
+            // the server decides the order, the row only stays where it was typed until the next fetch
+            MessageEntries::InlineResponse(values, response) => {
+                self.state = RequestState::Idle;
+
+                match response {
+                    Ok(()) => {
+                        let Some(inline) = self.inline.take() else { return Task::none(); };
+                        let position = inline.position.min(self.entries.len());
+
+                        self.entries.insert(position, values);
+                        self.shift_rows(position);
+                        self.total = self.total.map(|total| total + 1);
+                    },
+                    Err(err) => {
+                        if let Some(inline) = &mut self.inline {
+                            inline.error = Some(err.summary);
+                        }
+                    },
+                }
+
+                Task::none()
+            },
+            MessageEntries::Live(live::Event::Status(live)) => {
+                self.live = Some(live);
+
+                Task::none()
+            },
+            MessageEntries::Live(live::Event::Change(change)) => {
+                self.apply_change(table, change);
+
+                Task::none()
+            },
+            MessageEntries::Reveal(..) => unreachable!(),
+            MessageEntries::Expand(_) => unreachable!(),
+            MessageEntries::Duplicates(column) => {
+                self.duplicates = column;
+
+                Task::none()
+            },
+            MessageEntries::RelatedResponse(key, related) => {
+                let related = match related {
+                    Ok(Some(fields)) => Related::Found(fields),
+                    Ok(None) => Related::Missing,
+                    Err(err) => Related::Failed(err.summary),
+                };
+
+                self.related.insert(key, related);
+
+                Task::none()
+            },
+            MessageEntries::Settings(_) => unreachable!(),
+            MessageEntries::Banner(message) => {
+                banner::update(&mut self.message, message)
+            },
+        }
+    }
+
+    // the row to transpose when toggled on, the one in focus if there is one
+    fn transposable_row(&self) -> Option<usize> {
+        if self.transpose.is_some() {
+            return None;
+        }
+
+        self.editing.as_ref().map(|edit| edit.row)
+            .or(self.highlighted_row())
+            .or(self.save_preview)
+            .or(Some(0))
+    }
+
+    fn highlighted_row(&self) -> Option<usize> {
+        let (column, value) = self.highlight.as_ref()?;
+
+        self.entries.iter()
+            .position(|entry| entry.get(column).is_some_and(|cell| cell.as_ref() == Some(value)))
+    }
+
+    fn bulk_value(&self, table: &Table) -> Result<(String, Option<ColumnValue>), String> {
+        let column = self.bulk_draft.column.as_ref()
+            .and_then(|name| table.columns.iter().find(|column| &column.name == name))
+            .ok_or("no column selected".to_owned())?;
+
+        let value = ColumnValue::try_from_input(column, &self.bulk_draft.value)
+            .map_err(|err| err.to_string())?;
+
+        Ok((column.name.clone(), value))
+    }
+
+    // keys of the rows a bulk update goes to, every row matching the filter has to be loaded for it
+    fn bulk_rows(&self, table: &Table) -> Result<Vec<ColumnValue>, String> {
+        if self.truncated() {
+            let loaded = match self.total {
+                Some(total) => format!("only {} of {} matching rows are loaded", self.entries.len(), total),
+                None => format!("only the first {} matching rows are loaded", self.entries.len()),
+            };
+
+            return Err(format!("{}, narrow the filter or load every row first", loaded));
+        }
+
+        let rows: Vec<_> = self.entries.iter()
+            .filter_map(|entry| Self::primary_key(table, entry))
+            .collect();
+
+        if rows.is_empty() {
+            return Err("no loaded row has a primary key to update it by".to_owned());
+        }
+
+        Ok(rows)
+    }
+
+    // update one row at a time until every row is done
+    fn task_bulk_next(&mut self, table: &Table) -> iced::Task<MessageEntries> {
+        let Some(bulk) = &self.bulk else { return Task::none(); };
+
+        let next = bulk.rows.get(bulk.done).filter(|_| !bulk.stopped).cloned();
+
+        let Some(id) = next else {
+            let bulk = self.bulk.take().unwrap();
+            self.state = RequestState::Idle;
+
+            if bulk.stopped && bulk.failures.is_empty() {
+                self.message = Some(Banner::new(format!("bulk update: stopped after {} of {} rows", bulk.done, bulk.rows.len())));
+            }
+            else if !bulk.failures.is_empty() {
+                let summary = if bulk.stopped {
+                    format!("bulk update: stopped after {} of {} rows, {} failed", bulk.done, bulk.rows.len(), bulk.failures.len())
+                }
+                else {
+                    format!("bulk update: {} of {} rows failed", bulk.failures.len(), bulk.rows.len())
+                };
+                let raw = bulk.failures.join("\n");
+
+                self.message = Some(Banner::new(ErrorMessage {
+                    details: format!("{}\n\n{}", summary, raw),
+                    summary,
+                    raw: Some(raw),
+                    unauthorized: false,
+                    dry_run: false,
+                }));
+            }
+
+            return Task::none();
+        };
+
+        let key = id.clone();
+        let client = self.client.clone();
+        let table = table.clone();
+        let values = TableEntry::from([(bulk.column.clone(), bulk.value.clone())]);
+        let wrapper = || async move {
+            client.update(&table, id, values).await
+        };
+
+        iced::Task::perform(
+            wrapper(),
+            move |update| MessageEntries::BulkResponse(key.clone(), update.map_err(ErrorMessage::from)),
+        )
+    }
+
+    // parse every operand against the column type before building the comparison
+    fn draft_comp(&self, table: &Table) -> Result<(String, Comp<ColumnValue>), String> {
+        let column = self.draft.column.as_ref()
+            .and_then(|name| table.columns.iter().find(|column| &column.name == name))
+            .ok_or("no column selected".to_owned())?;
+
+        let comp = Self::parse_comp(column, self.draft.operator, &self.draft.operand)?;
+
+        Ok((column.name.clone(), comp))
+    }
+
+    // every condition of the query builder that's switched on
+    fn query_filter(table: &Table, query: &QueryBuilder) -> Result<Filter, String> {
+        query.conditions.iter()
+            .filter(|condition| condition.enabled)
+            .try_fold(Filter::new(), |filter, condition| {
+                let Some(column) = table.columns.iter().find(|column| column.name == condition.column) else { return Ok(filter); };
+
+                let comp = Self::parse_comp(column, condition.operator, &condition.operand)
+                    .map_err(|err| format!("{}: {}", column.name, err))?;
+
+                Ok(filter.with(&column.name, comp))
+            })
+    }
+
+    fn parse_comp(column: &TableColumn, operator: Operator, operand: &str) -> Result<Comp<ColumnValue>, String> {
+        let operands: Vec<_> = match operator.arity() {
+            Some(1) => vec![operand],
+            _ => operand.split(',').map(str::trim).collect(),
+        };
+
+        let values = operands.into_iter()
+            .map(|operand| {
+                match ColumnValue::try_from_str(column.clone(), operand) {
+                    Ok(Some(value)) => Ok(value),
+                    Ok(None) => Err(format!("`{}`: empty value", operand)),
+                    Err(err) => Err(format!("`{}`: {}", operand, err)),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        operator.comp(values)
+            .ok_or("wrong number of values".to_owned())
+    }
+
+    // clicking cells of the draft's column adds their values to an in/not-in set
+    fn picking(&self, column: &str) -> bool {
+        matches!(self.draft.operator, Operator::In | Operator::Nin)
+            && self.draft.column.as_deref() == Some(column)
+    }
+
+    fn open_insert(&mut self, table: Table, leaves: Vec<Table>) {
+        if self.offline {
+            return;
+        }
+
+        self.insert = Some(InsertDraft {
+            fields: Self::insert_fields(&table),
+            target: table,
+            leaves,
+            problems: Vec::new(),
+            error: None,
+        });
+    }
+
+    // the server assigns primary keys and the discriminator is set from the target table
+    fn insert_fields(table: &Table) -> Vec<(String, String)> {
+        table.columns.iter()
+            .filter(|column| !column.primary_key && !column.read_only && table.polymorphic.as_ref() != Some(&column.name))
+            .map(|column| {
+                let value = ColumnValue::default_for(column)
+                    .map(|value| match column.known_mapper() {
+                        Some(mapper) => mapper.edit_text(&value),
+                        None => value.to_string(),
+                    })
+                    .unwrap_or_default();
+
+                (column.name.clone(), value)
+            })
+            .collect()
+    }
+
+    // check every field at once so all mistakes can be fixed in one go
+    fn validate_insert(&self, current: &Table, target: &Table, fields: &[(String, String)]) -> Result<TableEntry, Vec<(String, String)>> {
+        // references can only be checked against this table, and only once all of it is loaded
+        let loaded = !self.truncated() && self.filter.is_empty();
+
+        let mut values = TableEntry::new();
+        let mut problems = Vec::new();
+
+        for (name, text) in fields {
+            let Some(column) = target.columns.iter().find(|column| &column.name == name) else { continue; };
+
+            let value = match ColumnValue::try_from_input(column, text) {
+                Ok(value) => value,
+                Err(err) => {
+                    let problem = match (err, column.known_mapper(), column.ty) {
+                        (ColumnParseError::Empty, _, _) => "required".to_owned(),
+                        (_, Some(mapper), _) => format!("expected something like {}", mapper.hint()),
+                        (_, None, ColumnType::Bool) => "expected true or false".to_owned(),
+                        (_, None, ColumnType::Int) => "expected a whole number".to_owned(),
+                        (_, None, ColumnType::Float) => "expected a number".to_owned(),
+                        (_, None, ColumnType::Json) => "expected valid JSON".to_owned(),
+                        (err, None, ColumnType::String) => err.to_string(),
+                    };
+
+                    problems.push((name.clone(), problem));
+
+                    continue;
+                },
+            };
+
+            let missing = value.as_ref()
+                .zip(column.foreign_keys.first())
+                .filter(|(_, key)| loaded && key.table == current.table)
+                .filter(|(value, key)| {
+                    !self.entries.iter().any(|entry| entry.get(&key.column).is_some_and(|other| other.as_ref() == Some(*value)))
+                });
+
+            if let Some((value, key)) = missing {
+                problems.push((name.clone(), format!("no row in `{}` has {} == {}", key.table, key.column, value)));
+            }
+
+            values.insert(name.clone(), value);
+        }
+
+        // the discriminator names the concrete entity being created
+        let discriminator = target.polymorphic.as_ref()
+            .and_then(|name| target.columns.iter().find(|column| &column.name == name));
+
+        if let Some(column) = discriminator {
+            match ColumnValue::try_from_str(column.clone(), &target.name) {
+                Ok(value) => {
+                    values.insert(column.name.clone(), value);
+                },
+                Err(err) => problems.push((column.name.clone(), err.to_string())),
+            }
+        }
+
+        target.order(&mut values);
+
+        if problems.is_empty() { Ok(values) }
+        else { Err(problems) }
+    }
+
+    // make room for a row added at `position`, everything kept per row from there on moves down one
+    fn shift_rows(&mut self, position: usize) {
+        let shift = |row: usize| if row >= position { row + 1 } else { row };
+
+        self.pending = self.pending.drain().map(|(row, pending)| (shift(row), pending)).collect();
+        self.edited = self.edited.drain().map(|((row, column), original)| ((shift(row), column), original)).collect();
+        self.expanded = self.expanded.drain().map(shift).collect();
+
+        if let Some(edit) = &mut self.editing {
+            edit.row = shift(edit.row);
+        }
+
+        if let Some(editor) = &mut self.json_editor {
+            editor.row = shift(editor.row);
+        }
+
+        self.save_preview = self.save_preview.map(shift);
+        self.transpose = self.transpose.map(shift);
+
+        self.copied = None;
+    }
+
+    // the row went away, everything kept per row past it moves up one
+    fn remove_row(&mut self, position: usize) {
+        let shift = |row: usize| match row.cmp(&position) {
+            std::cmp::Ordering::Less => Some(row),
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(row - 1),
+        };
+
+        self.pending = self.pending.drain().filter_map(|(row, pending)| Some((shift(row)?, pending))).collect();
+        self.edited = self.edited.drain().filter_map(|((row, column), original)| Some(((shift(row)?, column), original))).collect();
+        self.expanded = self.expanded.drain().filter_map(shift).collect();
+
+        self.editing = self.editing.take().and_then(|mut edit| {
+            edit.row = shift(edit.row)?;
+            Some(edit)
+        });
+
+        self.json_editor = self.json_editor.take().and_then(|mut editor| {
+            editor.row = shift(editor.row)?;
+            Some(editor)
+        });
+
+        self.save_preview = self.save_preview.and_then(shift);
+        self.transpose = self.transpose.and_then(shift);
+
+        if let Some(inline) = self.inline.as_mut().filter(|inline| inline.position > position) {
+            inline.position -= 1;
+        }
+
+        self.copied = None;
+    }
+
+    // a flag saved on its own, shown right away and rolled back if the server refuses it
+    fn toggle(&mut self, table: &Table, row: usize, column: String, value: Option<ColumnValue>) -> Task<MessageEntries> {
+        let Some(entry) = self.entries.get_mut(row) else { return Task::none(); };
+
+        // rows without a key can't be addressed alone, they keep to the batched save
+        let Some(id) = Self::primary_key(table, entry) else {
+            self.set_pending(row, column, value);
+
+            return Task::none();
+        };
+
+        let previous = entry.insert(column.clone(), value.clone()).flatten();
+
+        self.edited.entry((row, column.clone())).or_insert_with(|| previous.clone());
+
+        // a batched change of the same cell is superseded
+        if let Some(pending) = self.pending.get_mut(&row) {
+            pending.values.shift_remove(&column);
+            pending.rejected.retain(|rejected| *rejected != column);
+
+            if pending.values.is_empty() {
+                self.pending.remove(&row);
+            }
+        }
+
+        self.message = None;
+
+        let client = self.client.clone();
+        let table = table.clone();
+        let values = TableEntry::from([(column.clone(), value.clone())]);
+        let key = id.clone();
+        let wrapper = || async move {
+            client.update(&table, key, values).await
+        };
+
+        iced::Task::perform(
+            wrapper(),
+            move |update| MessageEntries::ToggleResponse(id.clone(), column.clone(), previous.clone(), value.clone(), update.map_err(ErrorMessage::from)),
+        )
+    }
+
+    // a change pushed by the server, rows are matched on their primary key
+    fn apply_change(&mut self, table: &Table, change: Change) {
+        match change {
+            // there's no telling whether a new row matches the filter, so it's left to the next refresh
+            Change::Insert(entry) => {
+                let known = Self::primary_key(table, &entry)
+                    .is_some_and(|id| self.row_by_key(table, &id).is_some());
+
+                if self.filter.is_empty() && !known {
+                    self.entries.push(entry);
+                    self.total = self.total.map(|total| total + 1);
+                }
+            },
+            // pending edits stay on top of the new values
+            Change::Update(entry) => {
+                let row = Self::primary_key(table, &entry)
+                    .and_then(|id| self.row_by_key(table, &id));
+
+                if let Some(row) = row {
+                    self.entries[row].extend(entry);
+                }
+            },
+            Change::Delete(id) => {
+                if let Some(row) = self.row_by_key(table, &id) {
+                    self.entries.remove(row);
+                    self.remove_row(row);
+                    self.total = self.total.map(|total| total.saturating_sub(1));
+                }
+            },
+        }
+    }
+
+    // typed text with what can't belong to the column's mapper format left out
+    fn masked(table: &Table, column: &str, value: String) -> String {
+        let mapper = table.columns.iter()
+            .find(|key| key.name == column)
+            .and_then(TableColumn::known_mapper);
+
+        match mapper {
+            Some(mapper) => mapper.mask(&value),
+            None => value,
+        }
+    }
+
+    fn is_editable(table: &Table, column: &TableColumn) -> bool {
+        let has_key = table.columns.iter().any(|column| column.primary_key);
+
+        has_key && !column.primary_key && !column.read_only && table.polymorphic.as_ref() != Some(&column.name)
+    }
+
+    fn primary_key(table: &Table, entry: &TableEntry) -> Option<ColumnValue> {
+        table.columns.iter()
+            .find(|column| column.primary_key)
+            .and_then(|column| entry.get(&column.name).cloned().flatten())
+    }
+
+    // where the row with this key is now
+    fn row_by_key(&self, table: &Table, id: &ColumnValue) -> Option<usize> {
+        self.entries.iter().position(|entry| Self::primary_key(table, entry).as_ref() == Some(id))
+    }
+
+    // the pending value if the cell was edited, otherwise the fetched one
+    fn value(&self, row: usize, column: &str) -> Option<&ColumnValue> {
+        self.pending.get(&row)
+            .and_then(|pending| pending.values.get(column))
+            .or_else(|| self.entries.get(row).and_then(|entry| entry.get(column)))
+            .and_then(Option::as_ref)
+    }
+
+    // pending values that really differ from the fetched row
+    fn changes(&self, table: &Table, row: usize) -> TableEntry {
+        let Some(pending) = self.pending.get(&row) else { return TableEntry::new(); };
+        let entry = self.entries.get(row);
+
+        let mut changes: TableEntry = pending.values.iter()
+            .filter(|(column, value)| entry.and_then(|entry| entry.get(*column)) != Some(*value))
+            .map(|(column, value)| (column.clone(), value.clone()))
+            .collect();
+
+        table.order(&mut changes);
+
+        changes
+    }
+
+    // a column the server left out of a row entirely, as opposed to one sent as null
+    fn is_absent(&self, row: usize, column: &str) -> bool {
+        let pending = self.pending.get(&row)
+            .is_some_and(|pending| pending.values.contains_key(column));
+
+        !pending && self.entries.get(row).is_some_and(|entry| !entry.contains_key(column))
+    }
+
+    fn edit(&mut self, table: &Table, row: usize, column: String) -> iced::Task<MessageEntries> {
+        let json = table.columns.iter()
+            .any(|key| key.name == column && key.ty == ColumnType::Json);
+
+        if json {
+            let text = match self.value(row, &column) {
+                Some(ColumnValue::Json(value)) => serde_json::to_string_pretty(value).unwrap_or_default(),
+                Some(value) => value.to_string(),
+                None => String::new(),
+            };
+
+            self.json_editor = Some(JsonEdit {
+                row,
+                column,
+                content: text_editor::Content::with_text(&text),
+                error: None,
+            });
+
+            return Task::none();
+        }
+
+        let mapper = table.columns.iter()
+            .find(|key| key.name == column)
+            .and_then(TableColumn::known_mapper);
+
+        let value = match (self.value(row, &column), mapper) {
+            (Some(value), Some(mapper)) => mapper.edit_text(value),
+            (Some(value), None) => value.to_string(),
+            (None, _) => String::new(),
+        };
+
+        self.editing = Some(CellEdit { row, column, value, invalid: false });
+
+        Task::batch([
+            text_input::focus(cell_input_id()),
+            text_input::select_all(cell_input_id()),
+        ])
+    }
+
+    // append to a list of operands, for set operators
+    fn add_operand(&mut self, value: String) {
+        if value.contains(',') {
+            self.draft.error = Some(format!("`{}`: values with commas can't be picked", value));
+
+            return;
+        }
+
+        let mut operands: Vec<_> = self.draft.operand.split(',')
+            .map(str::trim)
+            .filter(|operand| !operand.is_empty())
+            .map(str::to_owned)
+            .collect();
+
+        if !operands.contains(&value) {
+            operands.push(value);
+        }
+
+        self.draft.operand = operands.join(", ");
+        self.draft.error = None;
+    }
+
+    // record the edited cell as pending, keeping the edit open if it doesn't parse
+    fn commit(&mut self, table: &Table) -> Result<(), ()> {
+        let Some(edit) = &mut self.editing else { return Ok(()); };
+
+        // the column may be gone since, after a schema refresh
+        let Some(column) = table.columns.iter().find(|column| column.name == edit.column) else {
+            self.editing = None;
+            return Ok(());
+        };
+
+        // a float with nothing after the point still fits an int column
+        let parsed = ColumnValue::try_from_input(column, &edit.value)
+            .or_else(|err| Self::whole_number(column, &edit.value).map(Some).ok_or(err));
+
+        let value = match parsed {
+            Ok(value) => value,
+            Err(err) => {
+                edit.invalid = true;
+                self.message = Some(Banner::new(format!("{}: {}", column.name, err)));
+
+                return Err(());
+            },
+        };
+
+        let edit = self.editing.take().unwrap();
+        self.message = None;
+        self.type_hint = Self::type_hint(column, &edit.value, self.value(edit.row, &edit.column), value.as_ref());
+
+        self.set_pending(edit.row, edit.column, value);
+
+        Ok(())
+    }
+
+    fn whole_number(column: &TableColumn, text: &str) -> Option<ColumnValue> {
+        let number = text.trim().parse::<f64>().ok()?;
+
+        let whole = column.ty == ColumnType::Int
+            && number.fract() == 0.0
+            && number.abs() < i64::MAX as f64;
+
+        whole.then(|| ColumnValue::Int(number as i64))
+    }
+
+    // a heads-up for values that parse but may not be what was meant, it never blocks the edit
+    fn type_hint(column: &TableColumn, text: &str, current: Option<&ColumnValue>, value: Option<&ColumnValue>) -> Option<String> {
+        let text = text.trim();
+
+        // only whole floats, big ints and mapper formats such as currency are saved as typed
+        let float = text.parse::<i64>().is_err() && text.parse::<f64>().is_ok_and(|number| number.fract() == 0.0);
+
+        if let (true, ColumnType::Int, Some(value @ ColumnValue::Int(_))) = (float, column.ty, value) {
+            return Some(format!("`{}`: `{}` is a float, saved as the int {}", column.name, text, value));
+        }
+
+        // the server sent something else than the schema declares, saving changes the stored type
+        if let Some(current) = current.filter(|current| current.ty() != column.ty) {
+            return Some(format!("`{}`: the current value is a {}, it will be saved as a {}", column.name, current.ty(), column.ty));
+        }
+
+        if column.ty == ColumnType::String && column.choices().is_none() && column.known_mapper().is_none() && text.parse::<f64>().is_ok() {
+            return Some(format!("`{}`: `{}` looks like a number but is saved as a string", column.name, text));
+        }
+
+        None
+    }
+
+    fn set_pending(&mut self, row: usize, column: String, value: Option<ColumnValue>) {
+        let Some(entry) = self.entries.get(row) else { return; };
+        let original = entry.get(&column).cloned().flatten();
+
+        self.edited.entry((row, column.clone())).or_insert_with(|| original.clone());
+
+        let pending = self.pending.entry(row).or_default();
+        pending.rejected.retain(|rejected| *rejected != column);
+
+        if original == value {
+            pending.values.shift_remove(&column);
+        }
+        else {
+            pending.values.insert(column, value);
+        }
+
+        if pending.values.is_empty() {
+            self.pending.remove(&row);
+        }
+    }
+
+    // `focused` is whether the window showing the entries takes keyboard input
+    pub fn subscription(&self, focused: bool) -> Subscription<MessageEntries> {
+        // clears the copy confirmation once it's been seen
+        let tick = if self.copied.is_some() || self.column_copied.is_some() {
+            iced::time::every(Duration::from_millis(100)).map(|_| MessageEntries::CopyTick)
+        }
+        else {
+            Subscription::none()
+        };
+
+        let keys = event::listen_with(|event, status, _window| {
+            let iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event else { return None; };
+
+            // home/end belong to a focused text input
+            let ignored = matches!(status, event::Status::Ignored);
+
+            match key {
+                keyboard::Key::Named(keyboard::key::Named::Tab) => {
+                    Some(MessageEntries::EditMove(if modifiers.shift() { Move::Previous } else { Move::Next }))
+                },
+                keyboard::Key::Named(keyboard::key::Named::Escape) => Some(MessageEntries::Escape),
+                keyboard::Key::Named(keyboard::key::Named::Home) if ignored => Some(MessageEntries::ScrollTo(0.0)),
+                keyboard::Key::Named(keyboard::key::Named::End) if ignored => Some(MessageEntries::ScrollTo(1.0)),
+                _ => None,
+            }
+        });
+
+        let keys = if focused { keys } else { Subscription::none() };
+
+        Subscription::batch([keys, tick])
+    }
+
+    fn selection(&self) -> Selection {
+        if self.filter.is_empty() {
+            Selection::All
+        }
+        else {
+            Selection::Filter(self.filter.clone())
+        }
+    }
+
+    fn task_api_get(&mut self, table: &Table) -> iced::Task<MessageEntries> {
+        self.state = RequestState::Requesting;
+        self.message = None;
+
+        let client = self.client.clone();
+        let target = table.clone();
+        let limit = self.limit;
+        let selection = self.selection();
+        let wrapper = || async move {
+            let start = Instant::now();
+            let get = client.get(&target, selection, limit, 0).await;
+            (start.elapsed(), get)
+        };
+
+        iced::Task::perform(
+            wrapper(),
+            |(latency, get)| MessageEntries::GetResponse(latency, get.map_err(ErrorMessage::from)),
+        )
+    }
+
+    // the next page after the loaded entries, appended when it arrives
+    fn task_api_more(&mut self, table: &Table) -> iced::Task<MessageEntries> {
+        self.loading_more = true;
+
+        let client = self.client.clone();
+        let target = table.clone();
+        let limit = self.limit;
+        let offset = self.entries.len();
+        let selection = self.selection();
+        let wrapper = || async move {
+            client.get(&target, selection, limit, offset).await
+        };
+
+        iced::Task::perform(
+            wrapper(),
+            |get| MessageEntries::MoreResponse(get.map_err(ErrorMessage::from)),
+        )
+    }
+
+    pub fn view<'a>(&'a self, table: &'a Table, settings: &'a Settings) -> Element<'a, MessageEntries> {
+        // scrollable(text(format!("{:#?}", self.entries))).width(Length::Fill).into()
+
+        let widths = self.column_widths(table, settings);
+        let actions = !self.pending.is_empty();
+        let gutter = !self.offline;
+
+        // the header is laid out apart from the grid so it stays put when scrolling down,
+        // both use the same fixed widths to keep the columns lined up
+        let add = gutter.then(|| {
+            let add = button(text("+").size(settings.grid_text_size()))
+                .on_press(MessageEntries::InlineOpen(0))
+                .padding(0)
+                .style(button::text);
+
+            container(widget::labelled(add, "Add a row at the top"))
+                .width(GUTTER_WIDTH)
+                .into()
+        });
+
+        // pinned columns stay on the left with the row buttons, in a pane of their own that only scrolls down
+        let (pinned, widths): (Vec<_>, Vec<_>) = widths.into_iter()
+            .partition(|(column, _)| settings.column_pinned(&table.table, &column.name));
+
+        let pinning = !pinned.is_empty();
+
+        let lead: Vec<Element<MessageEntries>> = add
+            .into_iter()
+            .chain(actions.then(|| Space::with_width(ACTIONS_WIDTH).into()))
+            .chain(pinned.iter().map(|(column, width)| self.header_view(table, column, *width, settings)))
+            .collect();
+
+        let rest = widths.iter().map(|(column, width)| self.header_view(table, column, *width, settings));
+
+        let (lead, rest): (Vec<_>, Vec<_>) = if pinning { (lead, rest.collect()) } else { (Vec::new(), lead.into_iter().chain(rest).collect()) };
+
+        let headers: Vec<_> = rest.into_iter()
+            .intersperse_with(|| Space::with_width(8).into())
+            .chain([Space::with_width(SCROLLBAR_WIDTH).into()])
+            .collect();
+
+        let header = scrollable(row(headers))
+            .id(header_scrollable_id())
+            .direction(scrollable::Direction::Horizontal(scrollable::Scrollbar::new().width(0).scroller_width(0)))
+            .width(Length::Fill);
+
+        let header = row![]
+            .push_maybe(pinning.then(|| row(lead.into_iter().intersperse_with(|| Space::with_width(8).into()))))
+            .push_maybe(pinning.then(|| Space::with_width(8)))
+            .push(header);
+
+        let rows = GridRows {
+            heights: self.row_heights(table, settings),
+            duplicates: self.duplicate_rows().map(|(rows, _)| rows),
+            diff: self.diff(table),
+        };
+
+        let column_view = |(column, width): &(&TableColumn, f32)| {
+            self.column_view(table, column, *width, !self.offline && Self::is_editable(table, column), settings, &rows)
+        };
+
+        let lead: Vec<_> = gutter.then(|| self.gutter_view(settings, rows.heights.as_deref()))
+            .into_iter()
+            .chain(actions.then(|| self.actions_view(settings, rows.heights.as_deref())))
+            .chain(pinned.iter().map(column_view))
+            .collect();
+
+        let rest = widths.iter().map(column_view);
+
+        let (lead, rest): (Vec<_>, Vec<_>) = if pinning { (lead, rest.collect()) } else { (Vec::new(), lead.into_iter().chain(rest).collect()) };
+
+        let entries: Vec<_> = rest.into_iter()
+            .intersperse_with(|| vertical_rule(8).into())
+            .collect();
+
+        // padded like the grid under its horizontal scrollbar, so both scroll down equally far
+        let lead = pinning.then(|| {
+            let lead: Vec<_> = lead.into_iter()
+                .intersperse_with(|| vertical_rule(8).into())
+                .collect();
+
+            let lead = column![row(lead).height(Length::Shrink), Space::with_height(SCROLLBAR_WIDTH)];
+
+            scrollable(lead)
+                .id(pinned_scrollable_id())
+                .on_scroll(MessageEntries::PinnedScrolled)
+                .direction(scrollable::Direction::Vertical(scrollable::Scrollbar::new().width(0).scroller_width(0)))
+                .height(Length::Fill)
+        });
+
+        let loading = self.loading_more
+            .then(|| text("loading more…").size(settings.grid_text_size()).style(text::secondary));
+
+        let entries = column![row(entries).height(Length::Shrink)]
+            .push_maybe(loading)
+            .spacing(4);
+
+        let direction = scrollable::Direction::Both {
+            vertical: scrollable::Scrollbar::new(),
+            horizontal: scrollable::Scrollbar::new(),
+        };
+
+        let entries = scrollable(entries)
+            .id(entries_scrollable_id())
+            .on_scroll(MessageEntries::Scrolled)
+            .direction(direction)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        let entries = row![]
+            .push_maybe(lead)
+            .push_maybe(pinning.then(|| vertical_rule(8)))
+            .push(entries);
+
+        let message = self.message.as_ref()
+            .map(|message| message.view().map(MessageEntries::Banner));
+
+        let type_hint = self.type_hint.as_ref().map(|hint| {
+            row![
+                text(format!("⚠ {}", hint)).style(text::secondary).width(Length::Fill),
+                button(text("×"))
+                    .on_press(MessageEntries::TypeHintDismiss)
+                    .style(button::text),
+            ]
+            .padding([0, 4])
+            .align_y(iced::Alignment::Center)
+        });
+
+        // problems of the inline row, there's no room for them in its cells
+        let inline = self.inline.as_ref()
+            .map(|inline| {
+                inline.problems.iter()
+                    .map(|(column, problem)| format!("`{}`: {}", column, problem))
+                    .chain(inline.error.clone())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|lines| !lines.is_empty())
+            .map(|lines| text(format!("new row: {}", lines.join(", "))).style(text::danger));
+
+        let bulk = (!self.filter.is_empty() || self.bulk.is_some())
+            .then(|| self.bulk_view(table));
+
+        let export = self.export.as_ref().map(|export| self.export_view(export));
+        let diff = rows.diff.as_ref().map(|diff| self.diff_view(diff));
+        let legend = self.legend.then(|| self.legend_view(table, settings));
+
+        let content = column![self.toolbar_view(table, settings), self.filter_view(table)]
+            .push_maybe(legend)
+            .push_maybe(diff)
+            .push_maybe(bulk)
+            .push_maybe(export)
+            .push_maybe(self.minimap_view(table, settings))
+            .push_maybe(message)
+            .push_maybe(type_hint)
+            .push_maybe(inline);
+
+        let content = match self.transpose {
+            // an empty grid would look broken, the schema is what's wrong
+            _ if table.columns.is_empty() => content.push(
+                container(text("no columns defined for this table").style(text::secondary))
+                    .center(Length::Fill)
+            ),
+            Some(row) => content.push(self.transpose_view(table, row, settings)),
+            None => content
+                .push(header)
+                .push(horizontal_rule(8))
+                .push(entries),
+        }
+        .width(Length::Fill);
+
+        if self.bulk_draft.confirm {
+            let column = self.bulk_draft.column.clone().unwrap_or_default();
+
+            let rows = self.bulk_rows(table).map_or(0, |rows| rows.len());
+
+            // rows without a key can't be addressed one by one
+            let skipped = (rows < self.entries.len())
+                .then(|| text(format!("{} rows without a primary key are left as they are", self.entries.len() - rows)).style(text::secondary));
+
+            let dialog = column![text(format!("Set `{}` to `{}` on {} rows?", column, self.bulk_draft.value, rows))]
+                .push_maybe(skipped)
+                .push(
+                    row![
+                        button(text("Cancel")).on_press(MessageEntries::BulkCancel).style(button::secondary),
+                        button(text("Update")).on_press(MessageEntries::BulkApply).style(button::danger),
+                    ]
+                    .spacing(8)
+                )
+                .spacing(16);
+
+            widget::modal(content, dialog, MessageEntries::BulkCancel)
+        }
+        else if let Some(insert) = &self.insert {
+            widget::modal(content, self.insert_view(insert, settings), MessageEntries::InsertCancel)
+        }
+        else if let Some(row) = self.save_preview {
+            widget::modal(content, self.save_view(table, row), MessageEntries::SaveCancel)
+        }
+        else if let Some(query) = &self.query {
+            widget::modal(content, self.query_view(table, query), MessageEntries::QueryCancel)
+        }
+        else if let Some(editor) = &self.json_editor {
+            let error = editor.error.as_ref()
+                .map(|error| text(error.as_str()).style(text::danger));
+
+            let dialog = column![
+                text(editor.column.as_str()),
+                text_editor(&editor.content)
+                    .on_action(MessageEntries::JsonAction)
+                    .font(iced::Font::MONOSPACE)
+                    .height(320),
+            ]
+            .push_maybe(error)
+            .push(
+                row![
+                    button(text("Cancel")).on_press(MessageEntries::JsonCancel).style(button::secondary),
+                    button(text("Apply")).on_press(MessageEntries::JsonSave),
+                ]
+                .spacing(8)
+            )
+            .spacing(8);
+
+            widget::modal(content, dialog, MessageEntries::JsonCancel)
+        }
+        else if let Some(inspect) = &self.inspect {
+            let dialog = column![
+                text_editor(inspect)
+                    .on_action(MessageEntries::InspectAction)
+                    .height(320),
+                button(text("Close")).on_press(MessageEntries::InspectClose),
+            ]
+            .spacing(8);
+
+            widget::modal(content, dialog, MessageEntries::InspectClose)
+        }
+        else {
+            content.into()
+        }
+    }
+
+    fn query_view<'a>(&'a self, table: &Table, query: &'a QueryBuilder) -> Element<'a, MessageEntries> {
+        let idle = matches!(self.state, RequestState::Idle);
+
+        let conditions: Vec<Element<MessageEntries>> = query.conditions.iter()
+            .enumerate()
+            .map(|(index, condition)| {
+                row![
+                    checkbox(condition.column.as_str(), condition.enabled)
+                        .on_toggle(move |enabled| MessageEntries::QueryToggle(index, enabled))
+                        .width(160),
+                    pick_list(Operator::ALL, Some(condition.operator), move |operator| MessageEntries::QueryOperator(index, operator)),
+                    text_input("", &condition.operand)
+                        .on_input(move |operand| MessageEntries::QueryOperand(index, operand))
+                        .on_submit(MessageEntries::QueryApply),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center)
+                .into()
+            })
+            .collect();
+
+        // what would be sent, as far as it parses
+        let preview: Element<MessageEntries> = match Self::query_filter(table, query) {
+            Ok(filter) => {
+                let json = serde_json::to_string_pretty(&filter).unwrap_or_default();
+
+                container(text(json).font(iced::Font::MONOSPACE).size(12))
+                    .padding(8)
+                    .width(Length::Fill)
+                    .style(container::bordered_box)
+                    .into()
+            },
+            Err(err) => text(err).style(text::secondary).into(),
+        };
+
+        let error = query.error.as_ref()
+            .map(|error| text(error.as_str()).style(text::danger));
+
+        column![
+            text("Query builder").size(20),
+            container(scrollable(column(conditions).spacing(4))).max_height(320),
+            preview,
+        ]
+        .push_maybe(error)
+        .push(
+            row![
+                Space::with_width(Length::Fill),
+                button(text("Cancel")).on_press(MessageEntries::QueryCancel).style(button::secondary),
+                button(text("Apply")).on_press_maybe(idle.then_some(MessageEntries::QueryApply)),
+            ]
+            .spacing(8)
+        )
+        .spacing(12)
+        .into()
+    }
+
+    // old and new value of every changed field, then the body that will be sent
+    fn save_view(&self, table: &Table, row: usize) -> Element<MessageEntries> {
+        let changes = self.changes(table, row);
+        let original = self.entries.get(row);
+
+        let show = |value: Option<&ColumnValue>| value.map_or("null".to_owned(), ToString::to_string);
+
+        // schema order reads better than hash order
+        let fields: Vec<Element<MessageEntries>> = table.columns.iter()
+            .filter_map(|column| changes.get(&column.name).map(|value| (column, value)))
+            .map(|(column, value)| {
+                let old = original.and_then(|entry| entry.get(&column.name)).and_then(Option::as_ref);
+
+                row![
+                    text(column.name.clone()).width(120),
+                    text(show(old)).style(text::secondary).width(Length::Fill),
+                    text("→"),
+                    text(show(value.as_ref())).style(text::primary).width(Length::Fill),
+                ]
+                .spacing(8)
+                .into()
+            })
+            .collect();
+
+        let body = serde_json::to_string_pretty(&api::body(changes)).unwrap_or_default();
+
+        column![
+            text(format!("Save row {}?", row + 1)).size(20),
+            column(fields).spacing(4),
+            text("PATCH body").size(12).style(text::secondary),
+            container(text(body).font(iced::Font::MONOSPACE).size(12))
+                .padding(8)
+                .width(Length::Fill)
+                .style(container::bordered_box),
+            row![
+                Space::with_width(Length::Fill),
+                button(text("Keep editing")).on_press(MessageEntries::SaveCancel).style(button::secondary),
+                button(text("Save")).on_press(MessageEntries::SaveConfirm(row)),
+            ]
+            .spacing(8),
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    fn insert_view<'a>(&'a self, insert: &'a InsertDraft, settings: &Settings) -> Element<'a, MessageEntries> {
+        let idle = matches!(self.state, RequestState::Idle);
+        let table = &insert.target;
+
+        let fields: Vec<Element<MessageEntries>> = insert.fields.iter()
+            .map(|(name, value)| {
+                let column = table.columns.iter()
+                    .find(|column| &column.name == name);
+                let optional = column.is_some_and(|column| column.optional);
+                let choices = column.and_then(TableColumn::choices);
+                let invalid = insert.problems.iter().any(|(problem, _)| problem == name);
+
+                let name = name.clone();
+
+                let input: Element<MessageEntries> = match choices {
+                    Some(choices) => {
+                        let selected = choices.iter().find(|choice| *choice == value).cloned();
+                        let name = name.clone();
+
+                        pick_list(choices, selected, move |value| MessageEntries::InsertInput(name.clone(), value))
+                            .placeholder(if optional { "null" } else { "" })
+                            .into()
+                    },
+                    None => {
+                        let name = name.clone();
+
+                        let hint = column.and_then(TableColumn::known_mapper).map(|mapper| mapper.hint());
+                        let placeholder = match (optional, hint) {
+                            (true, Some(hint)) => format!("null or {}", hint),
+                            (false, Some(hint)) => hint,
+                            (true, None) => "null".to_owned(),
+                            (false, None) => String::new(),
+                        };
+
+                        text_input(&placeholder, value)
+                            .on_input(move |value| MessageEntries::InsertInput(name.clone(), value))
+                            .on_submit(MessageEntries::InsertSubmit)
+                            .into()
+                    },
+                };
+
+                row![
+                    text(name).width(160).style(if invalid { text::danger } else { text::default }),
+                    input,
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center)
+                .into()
+            })
+            .collect();
+
+        let error = insert.error.as_ref()
+            .map(|error| text(error.as_str()).style(text::danger));
+
+        let problems = (!insert.problems.is_empty()).then(|| {
+            let problems = insert.problems.iter()
+                .map(|(name, problem)| text(format!("`{}`: {}", name, problem)).style(text::danger).into());
+
+            iced::widget::column(problems).spacing(2)
+        });
+
+        let leaf = (!insert.leaves.is_empty()).then(|| {
+            let leaves: Vec<_> = insert.leaves.iter()
+                .map(|leaf| leaf.table.clone())
+                .collect();
+
+            row![
+                text("Type").width(160),
+                pick_list(leaves, Some(table.table.clone()), MessageEntries::InsertLeaf),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center)
+        });
+
+        column![
+            text(format!("New {}", settings.table_name(table))).size(20),
+        ]
+        .push_maybe(leaf)
+        .push(
+            scrollable(iced::widget::column(fields).spacing(4)).height(Length::Shrink)
+        )
+        .push_maybe(problems)
+        .push_maybe(error)
+        .push(
+            row![
+                button(text("Cancel")).on_press(MessageEntries::InsertCancel).style(button::secondary),
+                button(text("Insert")).on_press_maybe(idle.then_some(MessageEntries::InsertSubmit)),
+            ]
+            .spacing(8)
+        )
+        .spacing(8)
+        .into()
+    }
+
+    // set one column on every filtered row
+    fn bulk_view(&self, table: &Table) -> Element<MessageEntries> {
+        if let Some(bulk) = &self.bulk {
+            let failed = (!bulk.failures.is_empty())
+                .then(|| text(format!("{} failed", bulk.failures.len())).style(text::danger));
+
+            return row![
+                text(format!("Updating {} / {}", bulk.done, bulk.rows.len())),
+                progress_bar(0.0..=bulk.rows.len().max(1) as f32, bulk.done as f32)
+                    .height(8)
+                    .width(Length::Fill),
+            ]
+            .push_maybe(failed)
+            .push(
+                button(text(if bulk.stopped { "Stopping…" } else { "Stop" }))
+                    .on_press_maybe((!bulk.stopped).then_some(MessageEntries::BulkStop))
+                    .style(button::danger)
+            )
+            .spacing(8)
+            .padding(4)
+            .align_y(iced::Alignment::Center)
+            .into();
+        }
+
+        let idle = matches!(self.state, RequestState::Idle) && !self.offline;
+
+        let columns: Vec<_> = table.columns.iter()
+            .filter(|column| Self::is_editable(table, column))
+            .map(|column| column.name.clone())
+            .collect();
+
+        let error = self.bulk_draft.error.as_ref()
+            .map(|error| text(error.as_str()).style(text::danger));
+
+        let keyed = self.entries.iter()
+            .filter(|entry| Self::primary_key(table, entry).is_some())
+            .count();
+
+        row![
+            text("Set"),
+            pick_list(columns, self.bulk_draft.column.clone(), MessageEntries::BulkColumn)
+                .placeholder("column"),
+            text("to"),
+            text_input("value", &self.bulk_draft.value)
+                .on_input(MessageEntries::BulkValue)
+                .width(160),
+            button(text(format!("Update {} rows", keyed)))
+                .on_press_maybe((idle && self.bulk_draft.column.is_some()).then_some(MessageEntries::BulkConfirm)),
+        ]
+        .push_maybe(error)
+        .spacing(4)
+        .padding(4)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    // the whole table to a file, set up, running or finished
+    fn export_view(&self, export: &Export) -> Element<MessageEntries> {
+        let close = |label: &'static str| {
+            button(text(label))
+                .on_press(MessageEntries::ExportCancel)
+                .style(button::secondary)
+        };
+
+        let Some(path) = &export.path else {
+            return row![
+                text("Export every row as"),
+                pick_list(export::Format::ALL, Some(export.format), MessageEntries::ExportFormat),
+                button(text("Export")).on_press_maybe((!self.offline).then_some(MessageEntries::ExportStart)),
+                close("Cancel"),
+            ]
+            .spacing(8)
+            .padding(4)
+            .align_y(iced::Alignment::Center)
+            .into();
+        };
+
+        if export.done {
+            return row![
+                text(format!("Exported {} rows to {}", export.rows, path.display())).width(Length::Fill),
+                close("×"),
+            ]
+            .spacing(8)
+            .padding(4)
+            .align_y(iced::Alignment::Center)
+            .into();
+        }
+
+        let progress = match export.total {
+            Some(total) => text(format!("Exporting {} / {}", export.rows, total)),
+            None => text(format!("Exporting {} rows", export.rows)).width(Length::Fill),
+        };
+
+        // without a total there's nothing to measure against
+        let bar = export.total.map(|total| {
+            progress_bar(0.0..=total.max(export.rows).max(1) as f32, export.rows as f32)
+                .height(8)
+                .width(Length::Fill)
+        });
+
+        row![progress]
+            .push_maybe(bar)
+            .push(close("Cancel").style(button::danger))
+            .spacing(8)
+        .padding(4)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    fn filter_view(&self, table: &Table) -> Element<MessageEntries> {
+        let idle = matches!(self.state, RequestState::Idle);
+
+        // active filters in column order
+        let chips: Vec<Element<MessageEntries>> = table.columns.iter()
+            .filter_map(|column| {
+                self.filter.get(&column.name).map(|comp| (column, comp))
+            })
+            .map(|(column, comp)| {
+                let label = text(format!("{} {} ×", column.name, comp));
+
+                button(label)
+                    .on_press_maybe(idle.then_some(MessageEntries::FilterRemove(column.name.clone())))
+                    .style(button::secondary)
+                    .into()
+            })
+            .collect();
+
+        // any -> true -> false -> any
+        let toggles: Vec<Element<MessageEntries>> = table.columns.iter()
+            .filter(|column| column.ty == ColumnType::Bool)
+            .map(|column| {
+                let value = match self.filter.get(&column.name) {
+                    Some(Comp::Eq(ColumnValue::Bool(value))) => Some(*value),
+                    _ => None,
+                };
+
+                let (label, next) = match value {
+                    None => ("any", Some(true)),
+                    Some(true) => ("true", Some(false)),
+                    Some(false) => ("false", None),
+                };
+
+                button(text(format!("{}: {}", column.name, label)))
+                    .on_press_maybe(idle.then_some(MessageEntries::FilterBool(column.name.clone(), next)))
+                    .style(if value.is_some() { button::primary } else { button::text })
+                    .into()
+            })
+            .collect();
+
+        let offline: Option<Element<MessageEntries>> = self.offline
+            .then(|| text("stale (offline)").style(text::danger).into());
+
+        let truncated: Option<Element<MessageEntries>> = self.truncated()
+            .then(|| {
+                let shown = match self.total {
+                    Some(total) => format!("showing first {} of {}", self.entries.len(), total),
+                    None => format!("showing first {}", self.entries.len()),
+                };
+
+                // only our own cap can be lifted
+                let load_all = self.limit.map(|_| {
+                    button(text("Load all"))
+                        .on_press_maybe(idle.then_some(MessageEntries::RemoveLimit))
+                        .style(button::text)
+                });
+
+                row![
+                    text(format!("{} — refine your filter", shown)).style(text::danger),
+                ]
+                .push_maybe(load_all)
+                .spacing(4)
+                .align_y(iced::Alignment::Center)
+                .into()
+            });
+
+        row(offline.into_iter().chain(truncated).chain([self.draft_view(table), self.duplicates_view(table)]).chain(toggles).chain(chips))
+            .spacing(8)
+            .padding(4)
+            .wrap()
+            .into()
+    }
+
+    // every action on the table as one strip of icons, each disabled while it can't be used
+    fn toolbar_view(&self, table: &Table, settings: &Settings) -> Element<MessageEntries> {
+        let idle = matches!(self.state, RequestState::Idle);
+        // a table without columns has nothing to fill in or lay out
+        let columns = !table.columns.is_empty();
+        let writable = idle && !self.offline && columns;
+        let unsaved = self.editing.is_some() || !self.pending.is_empty();
+
+        let icon = |label: &'static str, tip: String, message: Option<MessageEntries>, active: bool| {
+            let button = button(text(label).center())
+                .on_press_maybe(message)
+                .padding([2, 8])
+                .style(if active { button::primary } else { button::text });
+
+            widget::labelled(button, tip)
+        };
+
+        let density = settings.density;
+
+        let audit: Vec<_> = table.columns.iter()
+            .filter(|column| column.is_audit())
+            .map(|column| column.name.as_str())
+            .collect();
+
+        let provenance = (!audit.is_empty()).then(|| {
+            let tip = if self.provenance {
+                "Show every column again".to_owned()
+            }
+            else {
+                format!("Show only the key and who/when columns: {}", audit.join(", "))
+            };
+
+            icon("⌚", tip, Some(MessageEntries::Provenance(!self.provenance)), self.provenance)
+        });
+
+        let tools = [
+            icon("⟳", "Refresh the rows".to_owned(), (idle && !unsaved).then_some(MessageEntries::Refresh), false),
+            icon("+", "New row".to_owned(), (writable && self.insert.is_none()).then_some(MessageEntries::InsertOpen), false),
+            icon("⌕", "Query builder".to_owned(), idle.then_some(MessageEntries::QueryOpen), self.query.is_some()),
+            icon(
+                "⧉",
+                if self.copy_mode { "Clicking a cell copies it, click to edit again" } else { "Copy cells by clicking them" }.to_owned(),
+                Some(MessageEntries::CopyMode(!self.copy_mode)),
+                self.copy_mode,
+            ),
+            icon(
+                "⇅",
+                if self.transpose.is_some() { "Back to the grid" } else { "Show one row with its columns top to bottom" }.to_owned(),
+                (columns && !self.entries.is_empty()).then(|| MessageEntries::Transpose(self.transposable_row())),
+                self.transpose.is_some(),
+            ),
+            icon("⤒", "Scroll to the top".to_owned(), Some(MessageEntries::ScrollTo(0.0)), false),
+            icon("⤓", "Scroll to the bottom".to_owned(), Some(MessageEntries::ScrollTo(1.0)), false),
+            icon(
+                "☰",
+                format!("{} rows, click for {}", density, density.next()),
+                Some(MessageEntries::Settings(MessageSettings::Density(density.next()))),
+                false,
+            ),
+        ];
+
+        // rows are matched by their primary key, there's nothing to match without one
+        let keyed = table.columns.iter().any(|column| column.primary_key);
+
+        let snapshot = match &self.snapshot {
+            _ if !keyed => icon("◉", "Snapshots need a primary key to match rows by".to_owned(), None, false),
+            None => icon("◉", "Snapshot the loaded rows to compare them later".to_owned(), (!self.entries.is_empty()).then_some(MessageEntries::Snapshot), false),
+            Some(snapshot) => icon(
+                "≠",
+                if snapshot.comparing { "Stop comparing to the snapshot" } else { "Refresh and compare to the snapshot" }.to_owned(),
+                ((idle && !unsaved) || snapshot.comparing).then_some(MessageEntries::SnapshotCompare(!snapshot.comparing)),
+                snapshot.comparing,
+            ),
+        };
+
+        let drop = self.snapshot.as_ref().map(|_| {
+            icon("○", "Drop the snapshot".to_owned(), Some(MessageEntries::SnapshotClear), false)
+        });
+
+        let bookmark = icon("⚑", "Save the table with this filter as a view in the sidebar".to_owned(), Some(MessageEntries::BookmarkStart), false);
+
+        let export = icon(
+            "⇩",
+            "Export every row of the table to a file".to_owned(),
+            (columns && !self.offline && self.export.is_none()).then_some(MessageEntries::ExportOpen),
+            self.export.is_some(),
+        );
+
+        let legend = icon(
+            "ℹ",
+            if self.legend { "Hide the legend" } else { "What the marks in the grid mean" }.to_owned(),
+            Some(MessageEntries::Legend(!self.legend)),
+            self.legend,
+        );
+
+        row(tools)
+            .push(snapshot)
+            .push_maybe(drop)
+            .push_maybe(provenance)
+            .push(bookmark)
+            .push(export)
+            .push(legend)
+            .spacing(2)
+            .padding([0, 4])
+            .align_y(iced::Alignment::Center)
+            .into()
+    }
+
+    fn duplicates_view(&self, table: &Table) -> Element<MessageEntries> {
+        let columns: Vec<_> = table.columns.iter()
+            .filter(|column| table.polymorphic.as_ref() != Some(&column.name))
+            .map(|column| column.name.clone())
+            .collect();
+
+        let clear = self.duplicates.is_some().then(|| {
+            button(text("×"))
+                .on_press(MessageEntries::Duplicates(None))
+                .style(button::text)
+        });
+
+        let pick = pick_list(columns, self.duplicates.clone(), |column| MessageEntries::Duplicates(Some(column)))
+            .placeholder("duplicates in…");
+
+        row![widget::labelled(pick, "Tint rows sharing a value in this column")]
+            .push_maybe(clear)
+            .align_y(iced::Alignment::Center)
+            .into()
+    }
+
+    fn draft_view(&self, table: &Table) -> Element<MessageEntries> {
+        let idle = matches!(self.state, RequestState::Idle);
+
+        let columns: Vec<_> = table.columns.iter()
+            .filter(|column| table.polymorphic.as_ref() != Some(&column.name))
+            .map(|column| column.name.clone())
+            .collect();
+
+        let placeholder = match self.draft.operator.arity() {
+            Some(1) => "value",
+            Some(_) => "min, max",
+            None => "value, value, ...",
+        };
+
+        let add = self.draft.column.is_some() && idle;
+
+        let operand = text_input(placeholder, &self.draft.operand)
+            .on_input(MessageEntries::FilterOperand)
+            .on_submit(MessageEntries::FilterAdd)
+            .width(160);
+
+        let choices = self.draft.column.as_ref()
+            .and_then(|name| table.columns.iter().find(|column| &column.name == name))
+            .and_then(TableColumn::choices)
+            .map(|choices| {
+                pick_list(choices.to_vec(), None::<String>, MessageEntries::FilterChoose)
+                    .placeholder("choose")
+            });
+
+        let error = self.draft.error.as_ref()
+            .map(|error| text(error.as_str()).style(text::danger));
+
+        let hint = self.draft.column.as_deref()
+            .filter(|column| self.draft.error.is_none() && self.picking(column))
+            .map(|_| text("click cells to add values").style(text::secondary));
+
+        row![
+            pick_list(columns, self.draft.column.clone(), MessageEntries::FilterColumn)
+                .placeholder("column"),
+            pick_list(Operator::ALL, Some(self.draft.operator), MessageEntries::FilterOperator),
+            operand,
+        ]
+        .push_maybe(choices)
+        .push(
+            button(text("Filter"))
+                .on_press_maybe(add.then_some(MessageEntries::FilterAdd))
+        )
+        .push_maybe(error)
+        .push_maybe(hint)
+        .spacing(4)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    // save/discard controls for rows with pending edits
+    fn actions_view(&self, settings: &Settings, heights: Option<&[f32]>) -> Element<MessageEntries> {
+        let size = settings.grid_text_size();
+        let padding = settings.density.padding();
+        let enabled = matches!(self.state, RequestState::Idle) && !self.offline;
+
+        let mut values: Vec<_> = (0..self.entries.len())
+            .map(|row| {
+                let cell: Element<MessageEntries> = if self.pending.contains_key(&row) {
+                    row![
+                        button(text("Save").size(size))
+                            .on_press_maybe(enabled.then_some(MessageEntries::SaveRow(row)))
+                            .padding(0)
+                            .style(button::text),
+                        button(text("Discard").size(size))
+                            .on_press(MessageEntries::DiscardRow(row))
+                            .padding(0)
+                            .style(button::text),
+                    ]
+                    .spacing(8)
+                    .into()
+                }
+                else {
+                    text("").size(size).into()
+                };
+
+                let height = heights.map_or(Length::Shrink, |heights| Length::Fixed(heights[row]));
+
+                container(cell)
+                    .padding([padding, 0])
+                    .height(height)
+                    .into()
+            })
+            .collect();
+
+        if let Some(inline) = &self.inline {
+            values.insert(inline.position, Space::with_height(self.inline_height(settings)).into());
+        }
+
+        iced::widget::column(values)
+            .width(ACTIONS_WIDTH)
+            .into()
+    }
+
+    // a single row as field | value lines, edited the same way as the grid
+    fn transpose_view<'a>(&'a self, table: &'a Table, row: usize, settings: &'a Settings) -> Element<'a, MessageEntries> {
+        let size = settings.grid_text_size();
+        let padding = settings.density.padding();
+        let enabled = matches!(self.state, RequestState::Idle) && !self.offline;
+
+        let glyph = size as f32 * 0.6;
+        let name_width = table.columns.iter()
+            .map(|column| column.name.chars().count() + 1)
+            .max()
+            .unwrap_or(0) as f32 * glyph;
+
+        let fields = table.columns.iter()
+            .filter(|column| table.polymorphic.as_ref() != Some(&column.name))
+            .map(|column| {
+                let name = if column.optional {
+                    text(format!("{}{}", column.name, Indicator::Optional.symbol())).size(size).style(text::secondary)
+                }
+                else {
+                    text(column.name.clone()).size(size)
+                };
+
+                let null = self.value(row, &column.name).is_none();
+                let dirty = self.pending.get(&row)
+                    .is_some_and(|pending| pending.values.contains_key(&column.name));
+
+                let value = text(self.display(table, row, column, settings))
+                    .size(size)
+                    .font(settings.grid_font.font_for(column))
+                    .style(if dirty { text::primary } else if null { text::secondary } else { text::default });
+
+                let edit = self.editing.as_ref()
+                    .filter(|edit| edit.row == row && edit.column == column.name);
+
+                let value: Element<MessageEntries> = match edit {
+                    Some(edit) => self.editor_view(column, edit, size),
+                    None if !self.offline && Self::is_editable(table, column) => mouse_area(value)
+                        .on_press(MessageEntries::EditStart(row, column.name.clone()))
+                        .into(),
+                    None => value.into(),
+                };
+
+                row![name.width(name_width), value]
+                    .spacing(8)
+                    .padding([padding, 0])
+                    .into()
+            });
+
+        let last = self.entries.len().saturating_sub(1);
+
+        let pending = self.pending.contains_key(&row).then(|| {
+            row![
+                button(text("Save")).on_press_maybe(enabled.then_some(MessageEntries::SaveRow(row))),
+                button(text("Discard")).on_press(MessageEntries::DiscardRow(row)).style(button::secondary),
+            ]
+            .spacing(8)
+        });
+
+        let navigation = row![
+            button(text("‹"))
+                .on_press_maybe((row > 0).then(|| MessageEntries::Transpose(Some(row - 1))))
+                .style(button::text),
+            text(format!("row {} of {}", row + 1, self.entries.len())),
+            button(text("›"))
+                .on_press_maybe((row < last).then(|| MessageEntries::Transpose(Some(row + 1))))
+                .style(button::text),
+        ]
+        .push_maybe(pending)
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+        column![
+            navigation,
+            horizontal_rule(8),
+            scrollable(iced::widget::column(fields).padding([0, 8]))
+                .width(Length::Fill)
+                .height(Length::Fill),
+        ]
+        .into()
+    }
+
+    // a "+" under every row to add one there, and the buttons that finish the inline row
+    fn gutter_view(&self, settings: &Settings, heights: Option<&[f32]>) -> Element<MessageEntries> {
+        let size = settings.grid_text_size();
+        let padding = settings.density.padding();
+        let idle = matches!(self.state, RequestState::Idle);
+
+        let small = |label: &'static str, message: Option<MessageEntries>| {
+            button(text(label).size(size))
+                .on_press_maybe(message)
+                .padding(0)
+                .style(button::text)
+        };
+
+        let mut values: Vec<Element<MessageEntries>> = (0..self.entries.len())
+            .map(|row| {
+                let height = heights.map_or(Length::Shrink, |heights| Length::Fixed(heights[row]));
+
+                container(widget::labelled(small("+", Some(MessageEntries::InlineOpen(row + 1))), "Add a row below"))
+                    .padding([padding, 0])
+                    .height(height)
+                    .into()
+            })
+            .collect();
+
+        if let Some(inline) = &self.inline {
+            let buttons = row![
+                widget::labelled(small("✓", idle.then_some(MessageEntries::InlineSubmit)), "Create the row"),
+                widget::labelled(small("×", Some(MessageEntries::InlineCancel)), "Discard the row"),
+            ]
+            .spacing(2);
+
+            let cell = container(buttons)
+                .padding([padding, 0])
+                .height(self.inline_height(settings));
+
+            values.insert(inline.position, cell.into());
+        }
+
+        iced::widget::column(values)
+            .width(GUTTER_WIDTH)
+            .into()
+    }
+
+    // the inline row holds a single line of inputs
+    fn inline_height(&self, settings: &Settings) -> f32 {
+        let size = settings.grid_text_size() as f32;
+        let padding = settings.density.padding() as f32;
+
+        (size * LINE_HEIGHT).ceil() + padding * 2.0
+    }
+
+    // a field of the inline row, columns the server fills in only say so
+    fn inline_cell<'a>(&'a self, inline: &'a InlineInsert, column: &TableColumn, settings: &Settings) -> Element<'a, MessageEntries> {
+        let size = settings.grid_text_size();
+        let padding = settings.density.padding();
+
+        let Some((_, value)) = inline.fields.iter().find(|(name, _)| name == &column.name) else {
+            return container(text("auto").size(size).style(text::secondary))
+                .padding([padding, 0])
+                .height(self.inline_height(settings))
+                .into();
+        };
+
+        let problem = inline.problems.iter()
+            .find(|(name, _)| name == &column.name)
+            .map(|(_, problem)| problem.clone());
+
+        let name = column.name.clone();
+
+        let field: Element<MessageEntries> = match column.choices() {
+            Some(choices) => {
+                let selected = choices.iter().find(|choice| *choice == value).cloned();
+
+                pick_list(choices.to_vec(), selected, move |choice| MessageEntries::InlineInput(name.clone(), choice))
+                    .text_size(size)
+                    .padding(0)
+                    .width(Length::Fill)
+                    .into()
+            },
+            None => {
+                let invalid = problem.is_some();
+                let placeholder = column.known_mapper().map_or(column.ty.to_string(), |mapper| mapper.hint());
+
+                text_input(&placeholder, value)
+                    .on_input(move |value| MessageEntries::InlineInput(name.clone(), value))
+                    .on_submit(MessageEntries::InlineSubmit)
+                    .size(size)
+                    .padding(0)
+                    .width(Length::Fill)
+                    .style(move |theme: &Theme, status| {
+                        let mut style = text_input::default(theme, status);
+                        if invalid {
+                            style.border.color = theme.palette().danger;
+                        }
+                        style
+                    })
+                    .into()
+            },
+        };
+
+        let field = match problem {
+            Some(problem) => widget::labelled(field, problem),
+            None => field,
+        };
+
+        container(field)
+            .padding([padding, 0])
+            .height(self.inline_height(settings))
+            .into()
+    }
+
+    // rows whose value in the chosen column appears more than once, and how many such values there are
+    fn duplicate_rows(&self) -> Option<(HashSet<usize>, usize)> {
+        let column = self.duplicates.as_ref()?;
+
+        // equal column values have the same type and text, nulls never count
+        let mut groups: HashMap<(ColumnType, String), Vec<usize>> = HashMap::new();
+        for row in 0..self.entries.len() {
+            if let Some(value) = self.value(row, column) {
+                groups.entry((value.ty(), value.to_string())).or_default().push(row);
+            }
+        }
+
+        let groups: Vec<_> = groups.into_values()
+            .filter(|rows| rows.len() > 1)
+            .collect();
+
+        let count = groups.len();
+
+        Some((groups.into_iter().flatten().collect(), count))
+    }
+
+    // a primary key that can be matched across fetches, equal the same way duplicates are
+    fn key_of(table: &Table, entry: &TableEntry) -> Option<(ColumnType, String)> {
+        Self::primary_key(table, entry).map(|key| (key.ty(), key.to_string()))
+    }
+
+    // how the loaded rows differ from the snapshot, fetched values only as pending edits aren't saved yet
+    fn diff(&self, table: &Table) -> Option<Diff> {
+        let snapshot = self.snapshot.as_ref().filter(|snapshot| snapshot.comparing)?;
+
+        let mut diff = Diff::default();
+        let mut seen = HashSet::new();
+
+        for (row, entry) in self.entries.iter().enumerate() {
+            let Some(key) = Self::key_of(table, entry) else { continue; };
+
+            match snapshot.rows.get(&key) {
+                Some(before) => {
+                    let changed: HashSet<_> = table.columns.iter()
+                        .filter(|column| entry.get(&column.name) != before.get(&column.name))
+                        .map(|column| column.name.clone())
+                        .collect();
+
+                    if !changed.is_empty() {
+                        diff.changed.insert(row, changed);
+                    }
+                },
+                None => {
+                    diff.added.insert(row);
+                },
+            }
+
+            seen.insert(key);
+        }
+
+        diff.removed = snapshot.rows.keys()
+            .filter(|key| !seen.contains(*key))
+            .map(|(_, key)| key.clone())
+            .collect();
+        diff.removed.sort();
+
+        Some(diff)
+    }
+
+    // marks that can show up with the table as it is, header buttons are always there
+    fn indicators(&self, table: &Table) -> Vec<Indicator> {
+        let optional = table.columns.iter().any(|column| column.optional);
+        let references = table.columns.iter().any(|column| !column.foreign_keys.is_empty());
+
+        let mismatch = || table.columns.iter().any(|column| {
+            (0..self.entries.len()).any(|row| self.value(row, &column.name).is_some_and(|value| value.is_mismatch(column.ty)))
+        });
+
+        let comparing = self.snapshot.as_ref().is_some_and(|snapshot| snapshot.comparing);
+
+        Indicator::ALL.into_iter()
+            .filter(|indicator| match indicator {
+                Indicator::Optional | Indicator::Null | Indicator::Nulls => optional,
+                Indicator::Reference | Indicator::Expand => references,
+                Indicator::Edited | Indicator::Unsaved | Indicator::Rejected => !self.offline,
+                Indicator::Mismatch => mismatch(),
+                Indicator::Duplicate => self.duplicates.is_some(),
+                Indicator::Added | Indicator::Changed => comparing,
+                Indicator::Pin | Indicator::Filter | Indicator::Wrap | Indicator::CopyColumn => true,
+            })
+            .collect()
+    }
+
+    fn legend_view(&self, table: &Table, settings: &Settings) -> Element<MessageEntries> {
+        let size = settings.grid_text_size();
+
+        let entries = self.indicators(table).into_iter().map(|indicator| {
+            let sample = text(indicator.symbol()).size(size);
+
+            let sample: Element<MessageEntries> = match indicator {
+                Indicator::Reference | Indicator::Expand | Indicator::Edited | Indicator::Unsaved => sample.style(text::primary).into(),
+                Indicator::Mismatch | Indicator::Rejected => sample.style(text::danger).into(),
+                Indicator::Optional | Indicator::Null => sample.style(text::secondary).into(),
+                Indicator::Duplicate => container(sample)
+                    .style(|theme: &Theme| container::Style {
+                        background: Some(theme.extended_palette().danger.weak.color.scale_alpha(0.4).into()),
+                        ..container::Style::default()
+                    })
+                    .into(),
+                Indicator::Added => container(sample)
+                    .style(|theme: &Theme| container::Style {
+                        background: Some(theme.extended_palette().success.weak.color.scale_alpha(0.4).into()),
+                        ..container::Style::default()
+                    })
+                    .into(),
+                Indicator::Changed => container(sample)
+                    .style(|_: &Theme| container::Style {
+                        background: Some(DIFF_CHANGED.into()),
+                        ..container::Style::default()
+                    })
+                    .into(),
+                _ => sample.into(),
+            };
+
+            row![
+                container(sample).width(40).center_x(40),
+                text(indicator.meaning()).size(size),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center)
+            .into()
+        });
+
+        container(iced::widget::column(entries).spacing(2))
+            .padding(8)
+            .width(Length::Fill)
+            .style(container::bordered_box)
+            .into()
+    }
+
+    fn diff_view(&self, diff: &Diff) -> Element<MessageEntries> {
+        let summary = text(format!(
+            "since the snapshot: {} added, {} changed, {} removed",
+            diff.added.len(),
+            diff.changed.len(),
+            diff.removed.len(),
+        ));
+
+        let removed = (!diff.removed.is_empty()).then(|| {
+            let keys: Vec<_> = diff.removed.iter().take(DIFF_REMOVED).map(String::as_str).collect();
+            let more = diff.removed.len().saturating_sub(DIFF_REMOVED);
+
+            let keys = if more > 0 { format!("{} and {} more", keys.join(", "), more) } else { keys.join(", ") };
+
+            text(format!("removed: {}", keys)).style(text::danger)
+        });
+
+        let close = button(text("×"))
+            .on_press(MessageEntries::SnapshotCompare(false))
+            .style(button::text);
+
+        row![summary]
+            .push_maybe(removed)
+            .push(Space::with_width(Length::Fill))
+            .push(widget::labelled(close, "Stop comparing, the snapshot is kept"))
+            .spacing(8)
+            .padding([0, 4])
+            .align_y(iced::Alignment::Center)
+            .into()
+    }
 
-                entries.1.update(table, message).map(MessageTable::Entries)
-            }
-            MessageTable::GetRequest(table) => {
-                self.state = RequestState::Requesting;
-                self.message = None;
+    // what shows a referenced row at a glance, text columns first as they're the likeliest to be names
+    fn related_fields(table: &Table, entry: &TableEntry) -> Vec<(String, String)> {
+        let mut columns: Vec<_> = table.columns.iter()
+            .filter(|column| !column.primary_key && table.polymorphic.as_ref() != Some(&column.name))
+            .collect();
 
-                self.task_api_get(&table, Selection::All)
-            },
-            MessageTable::GetResponse(table, entries) => {
-                self.state = RequestState::Idle;
+        columns.sort_by_key(|column| column.ty != ColumnType::String);
 
-                match entries {
-                    Ok(entries) => {
-                        self.entries = Some((
-                            table,
-                            StateEntries {
-                                client: self.client.clone(),
-                                entries,
-                                state: RequestState::Idle,
-                                message: None,
-                            },
-                        ))
-                    },
-                    Err(err) => self.message = Some(err),
-                }
+        columns.into_iter()
+            .take(RELATED_FIELDS)
+            .map(|column| {
+                let value = entry.get(&column.name)
+                    .cloned()
+                    .flatten()
+                    .map_or("null".to_owned(), |value| value.to_string());
 
-                Task::none()
-            },
+                (column.name.clone(), value)
+            })
+            .collect()
+    }
+
+    // the referenced row shown under a foreign key cell, for expanded rows
+    fn related(&self, row: usize, column: &TableColumn) -> Option<&Related> {
+        if !self.expanded.contains(&row) {
+            return None;
         }
+
+        let key = column.foreign_keys.first()?;
+        let value = self.value(row, &column.name)?;
+
+        self.related.get(&(key.table.clone(), key.column.clone(), value.to_string()))
     }
 
-    fn task_api_get(&self, table: &str, selection: Selection) -> iced::Task<MessageTable> {
-        let client = self.client.clone();
-        let table_name = table.to_owned();
-        let wrapper = || async move {
-            client.get(&table_name, selection).await
-        };
+    fn display(&self, table: &Table, row: usize, column: &TableColumn, settings: &Settings) -> String {
+        match self.value(row, &column.name) {
+            Some(value) => ColumnRenderer::for_column(column).format(value, settings),
+            None if self.is_absent(row, &column.name) => "·".to_owned(),
+            None => settings.null_display(&table.table, &column.name).text().to_owned(),
+        }
+    }
 
-        let table_name = table.to_owned();
-        iced::Task::perform(
-            wrapper(),
-            move |get| MessageTable::GetResponse(table_name.clone(), get.map_err(|err| err.to_string())),
+    // estimated width of each shown column, shared by the header and the grid below it
+    fn column_widths<'a>(&self, table: &'a Table, settings: &Settings) -> Vec<(&'a TableColumn, f32)> {
+        let glyph = settings.grid_text_size() as f32 * 0.6;
+
+        // without audit columns there's nothing to narrow down to
+        let provenance = self.provenance && table.columns.iter().any(TableColumn::is_audit);
+
+        table.columns.iter()
+            .filter(|column| table.polymorphic.as_ref() != Some(&column.name))
+            .filter(|column| !provenance || column.primary_key || column.is_audit())
+            .map(|column| {
+                let content = if settings.wrap(&table.table, &column.name) {
+                    WRAP_WIDTH
+                }
+                else {
+                    let chars = (0..self.entries.len().min(WIDTH_SAMPLE))
+                        .map(|row| self.display(table, row, column, settings).chars().count())
+                        .max()
+                        .unwrap_or(0);
+
+                    // lines of expanded referenced rows sit under the value
+                    let related = self.expanded.iter()
+                        .filter_map(|row| self.related(*row, column))
+                        .flat_map(|related| related.texts())
+                        .map(|line| line.chars().count())
+                        .max()
+                        .unwrap_or(0);
+
+                    chars.max(related) as f32 * glyph
+                };
+
+                let editing = self.editing.as_ref()
+                    .is_some_and(|edit| edit.column == column.name);
+
+                let content = if editing { content.max(CELL_EDIT_WIDTH) } else { content };
+
+                // room for the edited and mismatch markers, and the link and expand toggle of references
+                let extra = if column.foreign_keys.is_empty() { 6.0 } else { 8.0 };
+                let cells = content + glyph * extra;
+
+                // the header also holds the pin, filter, alignment, wrap, null and copy buttons
+                let header = (column.name.chars().count() + 12) as f32 * glyph;
+
+                (column, cells.max(header).ceil())
+            })
+            .collect()
+    }
+
+    // strip of column names sized like the grid, the ones in view highlighted
+    fn minimap_view(&self, table: &Table, settings: &Settings) -> Option<Element<MessageEntries>> {
+        // pinned columns are always in view, the map is of the ones that scroll
+        let widths: Vec<_> = self.column_widths(table, settings).into_iter()
+            .filter(|(column, _)| !settings.column_pinned(&table.table, &column.name))
+            .collect();
+
+        if widths.len() < MINIMAP_COLUMNS {
+            return None;
+        }
+
+        let total: f32 = widths.iter().map(|(_, width)| width).sum();
+
+        if let Some((_, width, content)) = self.scroll_span {
+            if content <= width {
+                return None;
+            }
+        }
+
+        let visible = self.scroll_span
+            .map(|(offset, width, content)| (offset / content, (offset + width) / content));
+
+        let mut start = 0.0;
+
+        let cells = widths.into_iter()
+            .map(|(column, width)| {
+                let (from, to) = (start / total, (start + width) / total);
+                start += width;
+
+                let shown = visible.is_some_and(|(left, right)| from < right && to > left);
+
+                let cell = button(text(column.name.clone()).size(10).wrapping(text::Wrapping::None))
+                    .on_press(MessageEntries::ScrollToColumn(from))
+                    .padding(0)
+                    .width(Length::FillPortion((width / 8.0).max(1.0) as u16))
+                    .style(if shown { button::primary } else { button::secondary });
+
+                widget::labelled(cell, column.name.clone())
+            });
+
+        Some(
+            row(cells)
+                .spacing(1)
+                .padding(4)
+                .into()
         )
     }
 
-    pub fn view(&self) -> Element<MessageTable> {
-        let tables: Vec<_> = self.tables.iter()
-            .map(|table| {
-                match table {
-                    TableDefinition::Single(table) => {
-                        Either::Left(iter::once(self.view_table(table)))
-                    },
-                    TableDefinition::Family { base: _, leaves } => {
-                        Either::Right(
-                            leaves.iter()
-                                .map(|table| self.view_table(table))
-                        )
-                    },
-                }
+    // wrapped cells are taller, so every cell of their row has to grow with them to keep the columns lined up
+    fn row_heights(&self, table: &Table, settings: &Settings) -> Option<Vec<f32>> {
+        let wrapped: Vec<_> = table.columns.iter()
+            .filter(|column| table.polymorphic.as_ref() != Some(&column.name))
+            .filter(|column| settings.wrap(&table.table, &column.name))
+            .collect();
+
+        if wrapped.is_empty() && self.expanded.is_empty() {
+            return None;
+        }
+
+        let keys: Vec<_> = table.columns.iter()
+            .filter(|column| !column.foreign_keys.is_empty())
+            .collect();
+
+        let size = settings.grid_text_size() as f32;
+        let padding = settings.density.padding() as f32;
+
+        // wrapped text is monospaced and broken at any glyph, so the line count is predictable
+        let per_line = ((WRAP_WIDTH / (size * 0.6)) as usize).max(1);
+
+        let heights = (0..self.entries.len())
+            .map(|row| {
+                let lines = wrapped.iter()
+                    .map(|column| {
+                        self.display(table, row, column, settings)
+                            .split('\n')
+                            .map(|line| line.chars().count().div_ceil(per_line).max(1))
+                            .sum::<usize>()
+                    })
+                    .max()
+                    .unwrap_or(1);
+
+                let related = keys.iter()
+                    .filter_map(|column| self.related(row, column))
+                    .map(|related| 1 + related.lines())
+                    .max()
+                    .unwrap_or(1);
+
+                let lines = lines.max(related);
+
+                (lines as f32 * size * LINE_HEIGHT).ceil() + padding * 2.0
             })
-            .flatten()
             .collect();
 
-        let tables = column(tables).width(256);
+        Some(heights)
+    }
+
+    // operator and value for one column, dropped down under its header
+    fn header_filter_view<'a>(&'a self, column: &'a TableColumn, filter: &'a HeaderFilter, size: u16) -> Element<'a, MessageEntries> {
+        let idle = matches!(self.state, RequestState::Idle);
+
+        let placeholder = match filter.operator.arity() {
+            Some(1) => "value",
+            Some(_) => "min, max",
+            None => "a, b, c",
+        };
+
+        let operand: Element<MessageEntries> = match column.choices().filter(|_| filter.operator.arity() == Some(1)) {
+            Some(choices) => {
+                let selected = choices.iter().find(|choice| **choice == filter.operand).cloned();
+
+                pick_list(choices, selected, MessageEntries::HeaderFilterOperand)
+                    .text_size(size)
+                    .width(Length::Fill)
+                    .into()
+            },
+            None => text_input(placeholder, &filter.operand)
+                .on_input(MessageEntries::HeaderFilterOperand)
+                .on_submit(MessageEntries::HeaderFilterApply)
+                .size(size)
+                .width(Length::Fill)
+                .into(),
+        };
+
+        let error = filter.error.as_ref()
+            .map(|error| text(error.as_str()).size(size).style(text::danger));
+
+        let small = |label: &'static str, message: Option<MessageEntries>| {
+            button(text(label).size(size))
+                .on_press_maybe(message)
+                .padding([0, 4])
+        };
+
+        let content = column![
+            pick_list(Operator::ALL, Some(filter.operator), MessageEntries::HeaderFilterOperator)
+                .text_size(size)
+                .width(Length::Fill),
+            operand,
+        ]
+        .push_maybe(error)
+        .push(
+            row![
+                small("Apply", idle.then_some(MessageEntries::HeaderFilterApply)),
+                small("Clear", idle.then_some(MessageEntries::HeaderFilterClear)).style(button::secondary),
+                small("×", Some(MessageEntries::HeaderFilterCancel)).style(button::text),
+            ]
+            .spacing(4)
+        )
+        .spacing(4);
+
+        container(content)
+            .padding(4)
+            .width(Length::Fill)
+            .style(container::bordered_box)
+            .into()
+    }
+
+    fn header_view<'a>(&'a self, table: &Table, column: &'a TableColumn, width: f32, settings: &Settings) -> Element<'a, MessageEntries> {
+        let size = settings.grid_text_size();
+
+        let align = settings.align(&table.table, column);
+        let wrap = settings.wrap(&table.table, &column.name);
+
+        let align_button = button(text(align.to_string()).size(size))
+            .on_press(MessageEntries::Settings(MessageSettings::ColumnAlign(table.table.clone(), column.name.clone(), align.next())))
+            .padding(0)
+            .style(button::text);
+
+        let wrap_button = button(text(Indicator::Wrap.symbol()).size(size))
+            .on_press(MessageEntries::Settings(MessageSettings::ColumnWrap(table.table.clone(), column.name.clone(), !wrap)))
+            .padding(0)
+            .style(if wrap { button::primary } else { button::text });
 
-        let entries = if let Some(entries) = &self.entries {
-            let table = self.get_selected_table().unwrap();
-            entries.1.view(table).map(MessageTable::Entries)
+        // optional columns are marked so it's clear where blanks are allowed
+        let name = if column.optional {
+            text(format!("{}{}", column.name, Indicator::Optional.symbol())).size(size).style(text::secondary)
         }
         else {
-            Space::new(Length::Fill, Length::Fill).into()
+            text(column.name.clone()).size(size)
         };
 
-        row![
-            tables,
-            vertical_rule(0),
-            entries,
+        let label = match (column.optional, column.read_only) {
+            (true, false) => "Optional, may be null",
+            (false, false) => "Required",
+            (_, true) => "Read-only, set by the server",
+        };
+
+        let name = widget::labelled(name, label);
+
+        // only nullable columns get to choose how their nulls read
+        let null_button = column.optional.then(|| {
+            let null = settings.column_null(&table.table, &column.name);
+
+            let label = match null {
+                Some(null) => format!("Nulls shown as {}, click to change", null),
+                None => "Nulls follow the settings, click to change".to_owned(),
+            };
+
+            let button = button(text(Indicator::Nulls.symbol()).size(size))
+                .on_press(MessageEntries::Settings(MessageSettings::ColumnNull(table.table.clone(), column.name.clone(), NullDisplay::next(null))))
+                .padding(0)
+                .style(if null.is_some() { button::primary } else { button::text });
+
+            widget::labelled(button, label)
+        });
+
+        let copied = self.column_copied.as_ref()
+            .filter(|copied| copied.0 == column.name)
+            .map(|copied| copied.1);
+
+        let copy_button = button(text(if copied.is_some() { "✓" } else { Indicator::CopyColumn.symbol() }).size(size))
+            .on_press(MessageEntries::CopyColumn(column.name.clone(), settings.null_copy))
+            .padding(0)
+            .style(if copied.is_some() { button::success } else { button::text });
+
+        let copy_label = match copied {
+            Some(lines) => format!("Copied {} lines", lines),
+            None => "Copy the whole column, one value per line".to_owned(),
+        };
+
+        let filtered = self.filter.get(&column.name);
+
+        let filter_button = button(text(Indicator::Filter.symbol()).size(size))
+            .on_press(MessageEntries::HeaderFilterOpen(column.name.clone()))
+            .padding(0)
+            .style(if filtered.is_some() { button::primary } else { button::text });
+
+        let filter_label = match filtered {
+            Some(comp) => format!("Filtered: {} {}, click to change", column.name, comp),
+            None => "Filter this column".to_owned(),
+        };
+
+        let pinned = settings.column_pinned(&table.table, &column.name);
+
+        let pin_button = button(text(Indicator::Pin.symbol()).size(size))
+            .on_press(MessageEntries::Settings(MessageSettings::ColumnPin(table.table.clone(), column.name.clone(), !pinned)))
+            .padding(0)
+            .style(if pinned { button::primary } else { button::text });
+
+        let header = row![
+            name,
+            widget::labelled(pin_button, if pinned { "Pinned to the left, click to unpin" } else { "Pin to the left" }),
+            widget::labelled(filter_button, filter_label),
+            widget::labelled(align_button, align.label()),
+            widget::labelled(wrap_button, if wrap { "Wrapping text, click to stop" } else { "Wrap text" }),
         ]
-        .into()
+        .push_maybe(null_button)
+        .push(widget::labelled(copy_button, copy_label))
+        .spacing(4);
+
+        let popover = self.header_filter.as_ref()
+            .filter(|filter| filter.column == column.name)
+            .map(|filter| self.header_filter_view(column, filter, size));
+
+        let header = column![header].push_maybe(popover).spacing(4);
+
+        container(header)
+            .width(width)
+            .clip(true)
+            .into()
     }
 
-    fn view_table(&self, table: &Table) -> Element<MessageTable> {
-        let label = text(table.pretty_name())
-            .width(Length::Fill)
-            .center();
+    // the inline editor suited to the column, every one of them ends up as text parsed by `commit`
+    fn editor_view<'a>(&'a self, column: &TableColumn, edit: &'a CellEdit, size: u16) -> Element<'a, MessageEntries> {
+        if let Some(choices) = column.choices() {
+            let selected = choices.iter().find(|choice| **choice == edit.value).cloned();
 
-        let idle = matches!(self.state, RequestState::Idle);
-        let selected = self.entries.as_ref()
-            .map_or(false, |entries| entries.0 == table.table);
+            return pick_list(choices.to_vec(), selected, MessageEntries::EditChoose)
+                .text_size(size)
+                .padding(0)
+                .width(CELL_EDIT_WIDTH)
+                .into();
+        }
+
+        let small = |label: &'a str, message: MessageEntries| {
+            button(text(label).size(size))
+                .on_press(message)
+                .padding([0, 4])
+                .style(button::secondary)
+        };
 
-        button(label)
-            .on_press_maybe((idle && !selected).then_some(MessageTable::GetRequest(table.table.clone())))
+        if column.ty == ColumnType::Bool {
+            // nullable bools can also be cleared
+            let clear = column.optional.then(|| small("∅", MessageEntries::EditToggle(None)));
+
+            let label = if edit.value.is_empty() { "null".to_owned() } else { edit.value.clone() };
+
+            return row![
+                checkbox(label, edit.value == "true")
+                    .on_toggle(|checked| MessageEntries::EditToggle(Some(checked)))
+                    .text_size(size)
+                    .size(size),
+            ]
+            .push_maybe(clear)
+            .spacing(4)
+            .align_y(iced::Alignment::Center)
+            .width(CELL_EDIT_WIDTH)
+            .into();
+        }
+
+        let invalid = edit.invalid;
+
+        let hint = column.known_mapper().map(|mapper| mapper.hint()).unwrap_or_default();
+
+        let input = text_input(&hint, &edit.value)
+            .id(cell_input_id())
+            .on_input(MessageEntries::EditInput)
+            .on_submit(MessageEntries::EditMove(Move::Down))
+            .size(size)
+            .padding(0)
             .width(Length::Fill)
+            .style(move |theme: &Theme, status| {
+                let mut style = text_input::default(theme, status);
+                if invalid {
+                    style.border.color = theme.palette().danger;
+                }
+                style
+            });
+
+        let extra: Vec<Element<MessageEntries>> = if column.time_format().is_some() {
+            vec![small("Now", MessageEntries::EditNow).into()]
+        }
+        else if matches!(column.ty, ColumnType::Int | ColumnType::Float) {
+            vec![
+                small("−", MessageEntries::EditStep(-1)).into(),
+                small("+", MessageEntries::EditStep(1)).into(),
+            ]
+        }
+        else {
+            Vec::new()
+        };
+
+        row![input]
+            .extend(extra)
+            .spacing(2)
+            .align_y(iced::Alignment::Center)
+            .width(CELL_EDIT_WIDTH)
             .into()
     }
-}
 
-#[derive(Debug, Clone)]
-enum MessageEntries {
+    fn column_view(&self, table: &Table, column: &TableColumn, width: f32, editable: bool, settings: &Settings, rows: &GridRows) -> Element<MessageEntries> {
+        let size = settings.grid_text_size();
+        let padding = settings.density.padding();
 
-}
+        let align = settings.align(&table.table, column);
+        let wrap = settings.wrap(&table.table, &column.name);
+        let font = settings.grid_font.font_for(column);
+        let renderer = ColumnRenderer::for_column(column);
 
-#[derive(Debug)]
-struct StateEntries {
-    client: Client,
-    entries: Vec<TableEntry>,
-    state: RequestState,
-    message: Option<String>,
-}
+        let highlighted_row = self.highlighted_row();
+        let picking = self.picking(&column.name);
 
-impl StateEntries {
-    pub fn update(&mut self, table: &Table, message: MessageEntries) -> iced::Task<MessageEntries> {
-        match message {
+        let mut values: Vec<_> = (0..self.entries.len())
+            .map(|row| {
+                let copied = self.copied.as_ref()
+                    .filter(|copied| copied.0 == row && copied.1 == column.name)
+                    .map(|copied| if copied.2 { "copied (empty)" } else { "copied!" });
+
+                let value = match copied {
+                    Some(copied) => copied.to_owned(),
+                    None => self.display(table, row, column, settings),
+                };
+
+                let absent = self.is_absent(row, &column.name);
+                let null = self.value(row, &column.name).is_none();
+                let pending = self.pending.get(&row);
+                let dirty = pending.is_some_and(|pending| pending.values.contains_key(&column.name));
+                let rejected = pending.is_some_and(|pending| pending.rejected.contains(&column.name));
+
+                let mut value = text(value)
+                    .size(size)
+                    .font(font)
+                    .align_x(iced::Alignment::from(align));
+
+                value = if wrap {
+                    value
+                        .font(iced::Font::MONOSPACE)
+                        .wrapping(text::Wrapping::Glyph)
+                        .width(WRAP_WIDTH)
+                }
+                else {
+                    value.wrapping(text::Wrapping::None)
+                };
+
+                if copied.is_some() {
+                    value = value.style(text::success);
+                }
+                else if rejected {
+                    value = value.style(text::danger);
+                }
+                else if dirty {
+                    value = value.style(text::primary);
+                }
+                else if absent || null {
+                    value = value.style(text::secondary);
+                }
+
+                (row, value)
+            })
+            .map(|(row, value)| {
+                let edit = self.editing.as_ref()
+                    .filter(|edit| edit.row == row && edit.column == column.name);
+
+                let cell: Element<MessageEntries> = if let Some(edit) = edit {
+                    self.editor_view(column, edit, size)
+                }
+                else if picking {
+                    mouse_area(value)
+                        .on_press(MessageEntries::FilterPick(row, column.name.clone()))
+                        .on_right_press(MessageEntries::Inspect(row))
+                        .into()
+                }
+                else if self.copy_mode {
+                    mouse_area(value)
+                        .on_press(MessageEntries::CopyCell(row, column.name.clone()))
+                        .on_right_press(MessageEntries::Inspect(row))
+                        .into()
+                }
+                else if editable {
+                    mouse_area(value)
+                        .on_press(MessageEntries::EditStart(row, column.name.clone()))
+                        .on_right_press(MessageEntries::Inspect(row))
+                        .into()
+                }
+                else {
+                    mouse_area(value)
+                        .on_press(MessageEntries::Inspect(row))
+                        .on_right_press(MessageEntries::Inspect(row))
+                        .into()
+                };
+
+                // link foreign keys to the row they reference
+                let link = renderer.reference()
+                    .zip(self.value(row, &column.name))
+                    .map(|(key, value)| {
+                        let link = mouse_area(text(Indicator::Reference.symbol()).size(size).style(text::primary))
+                            .on_press(MessageEntries::Reveal(key.table.clone(), key.column.clone(), value.clone()));
+
+                        widget::labelled(link, format!("Open referenced row in `{}`", key.table))
+                    });
+
+                let expanded = self.expanded.contains(&row);
+
+                let expand = renderer.reference()
+                    .filter(|_| self.value(row, &column.name).is_some())
+                    .map(|key| {
+                        let expand = mouse_area(text(if expanded { "▾" } else { Indicator::Expand.symbol() }).size(size).style(text::primary))
+                            .on_press(MessageEntries::Expand(row));
+
+                        widget::labelled(expand, if expanded { "Collapse".to_owned() } else { format!("Show the referenced row from `{}` here", key.table) })
+                    });
+
+                let related = self.related(row, column).map(|related| {
+                    let lines = related.texts()
+                        .into_iter()
+                        .map(|line| text(line).size(size).style(text::secondary).wrapping(text::Wrapping::None).into());
+
+                    iced::widget::column(lines)
+                });
+
+                // cells changed this session show where they started
+                let original = self.edited.get(&(row, column.name.clone()))
+                    .filter(|original| original.as_ref() != self.value(row, &column.name));
+
+                let marker = original.map(|_| text(Indicator::Edited.symbol()).size(size / 2).style(text::primary));
 
+                // a value the declared type couldn't be made of, sorting and filtering may treat it apart
+                let mismatch = self.value(row, &column.name)
+                    .filter(|value| value.is_mismatch(column.ty))
+                    .map(|value| {
+                        let marker = text(Indicator::Mismatch.symbol()).size(size).style(text::danger);
+
+                        widget::labelled(marker, format!("Stored as {}, the column is {}", value.ty(), column.ty))
+                    });
+
+                let cell = row![cell]
+                    .push_maybe(mismatch)
+                    .push_maybe(marker)
+                    .push_maybe(link)
+                    .push_maybe(expand)
+                    .spacing(4);
+
+                let cell = column![cell].push_maybe(related);
+
+                let cell: Element<MessageEntries> = match original {
+                    Some(original) => {
+                        let dirty = self.pending.get(&row)
+                            .is_some_and(|pending| pending.values.contains_key(&column.name));
+
+                        let unsaved = if dirty { " (unsaved)" } else { "" };
+                        let now = self.value(row, &column.name).map_or("null".to_owned(), ColumnValue::to_string);
+                        let was = original.as_ref().map_or("null".to_owned(), ColumnValue::to_string);
+
+                        let tip = container(text(format!("was: {}\nnow: {}{}", was, now, unsaved)).size(size))
+                            .padding(4)
+                            .style(container::bordered_box);
+
+                        tooltip(cell, tip, tooltip::Position::Top).into()
+                    },
+                    None => cell.into(),
+                };
+
+                let highlighted = highlighted_row == Some(row);
+                let duplicate = rows.duplicates.as_ref().is_some_and(|duplicates| duplicates.contains(&row));
+                let added = rows.diff.as_ref().is_some_and(|diff| diff.added.contains(&row));
+                let changed = rows.diff.as_ref().and_then(|diff| diff.changed.get(&row)).is_some_and(|columns| columns.contains(&column.name));
+                let height = rows.heights.as_ref().map_or(Length::Shrink, |heights| Length::Fixed(heights[row]));
+
+                container(cell)
+                    .padding([padding, 0])
+                    .height(height)
+                    .style(move |theme: &Theme| {
+                        if highlighted {
+                            container::Style {
+                                background: Some(theme.extended_palette().primary.weak.color.into()),
+                                ..container::Style::default()
+                            }
+                        }
+                        else if duplicate {
+                            container::Style {
+                                background: Some(theme.extended_palette().danger.weak.color.scale_alpha(0.4).into()),
+                                ..container::Style::default()
+                            }
+                        }
+                        else if added {
+                            container::Style {
+                                background: Some(theme.extended_palette().success.weak.color.scale_alpha(0.4).into()),
+                                ..container::Style::default()
+                            }
+                        }
+                        else if changed {
+                            container::Style {
+                                background: Some(DIFF_CHANGED.into()),
+                                ..container::Style::default()
+                            }
+                        }
+                        else {
+                            container::Style::default()
+                        }
+                    })
+                    .into()
+            })
+            .collect();
+
+        if let Some(inline) = &self.inline {
+            values.insert(inline.position, self.inline_cell(inline, column, settings));
         }
+
+        iced::widget::column(values)
+            .align_x(iced::Alignment::from(align))
+            .width(width)
+            .clip(true)
+            .into()
     }
+}
 
-    pub fn view(&self, table: &Table) -> Element<MessageEntries> {
-        // scrollable(text(format!("{:#?}", self.entries))).width(Length::Fill).into()
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let entries: Vec<_> = table.columns.iter()
-            .filter(|column| table.polymorphic.as_ref() != Some(&column.name))
-            .map(|column| self.column_view(column))
-            .intersperse_with(|| vertical_rule(8).into())
-            .collect();
+    #[test]
+    fn unauthorized_responses_are_retried() {
+        let refused = ErrorMessage { unauthorized: true, ..ErrorMessage::new("error: `token expired`".to_owned()) };
+        let failed = ErrorMessage::new("error: `server error`".to_owned());
 
-        let entries = row(entries).height(Length::Shrink);
+        let retry = StateTable::retry_for(&MessageTable::TablesResponse(Duration::ZERO, Err(refused.clone())));
+        assert!(matches!(retry, Some(MessageTable::TablesRequest)));
 
-        let direction = scrollable::Direction::Both {
-            vertical: scrollable::Scrollbar::new(),
-            horizontal: scrollable::Scrollbar::new(),
+        let retry = StateTable::retry_for(&MessageTable::GetResponse("item".to_owned(), Duration::ZERO, Err(refused)));
+        assert!(matches!(retry, Some(MessageTable::GetRequest(table)) if table == "item"));
+
+        assert!(StateTable::retry_for(&MessageTable::TablesResponse(Duration::ZERO, Err(failed))).is_none());
+    }
+
+    fn column(name: &str, ty: ColumnType, mapper: Option<&str>) -> TableColumn {
+        TableColumn {
+            name: name.to_owned(),
+            ty,
+            optional: true,
+            primary_key: false,
+            foreign_keys: Vec::new(),
+            mapper: mapper.map(str::to_owned),
+            values: None,
+            read_only: false,
+        }
+    }
+
+    #[test]
+    fn float_hint_only_for_whole_floats() {
+        let hint = |column: &TableColumn, text: &str| {
+            let value = ColumnValue::try_from_input(column, text).ok().flatten()
+                .or_else(|| StateEntries::whole_number(column, text));
+
+            StateEntries::type_hint(column, text, None, value.as_ref())
         };
 
-        scrollable(entries)
-            .direction(direction)
-            .width(Length::Fill)
-            .height(Length::Fill).into()
+        let stock = column("stock", ColumnType::Int, None);
+        assert_eq!(hint(&stock, "5.0").as_deref(), Some("`stock`: `5.0` is a float, saved as the int 5"));
+        assert_eq!(hint(&stock, "5"), None);
+        assert_eq!(hint(&stock, "12345678901234567890"), None);
+
+        let price = column("price", ColumnType::Int, Some("currency"));
+        assert_eq!(hint(&price, "1,234"), None);
     }
 
-    fn column_view(&self, column: &TableColumn) -> Element<MessageEntries> {
-        let header = text(column.name.clone());
+    fn table() -> Table {
+        Table {
+            name: "Item".to_owned(),
+            table: "item".to_owned(),
+            polymorphic: None,
+            columns: vec![
+                TableColumn { primary_key: true, optional: false, ..column("id", ColumnType::Int, None) },
+                column("stock", ColumnType::Int, None),
+            ],
+        }
+    }
 
-        let values: Vec<_> = self.entries.iter()
-            .map(|entry| entry.get(&column.name).unwrap())
-            .map(|value| {
-                match value {
-                    Some(value) => value.to_string(),
-                    None => "".to_owned(),
-                }
-            })
-            .map(text)
-            .map(Into::into)
+    fn entries() -> StateEntries {
+        let rows = (1..=3)
+            .map(|id| TableEntry::from([
+                ("id".to_owned(), Some(ColumnValue::Int(id))),
+                ("stock".to_owned(), Some(ColumnValue::Int(5))),
+            ]))
             .collect();
 
-        column![
-            header,
-            horizontal_rule(8),
-            iced::widget::column(values),
-        ]
-        .width(Length::Shrink)
-        .into()
+        StateEntries::new(Client::new("http://localhost".to_owned()), rows, false, None)
+    }
+
+    #[test]
+    fn refetching_waits_for_unsaved_edits() {
+        let table = table();
+        let mut entries = entries();
+        entries.set_pending(1, "stock".to_owned(), Some(ColumnValue::Int(6)));
+
+        let refetches = [
+            MessageEntries::FilterBool("stock".to_owned(), Some(true)),
+            MessageEntries::FilterRemove("stock".to_owned()),
+            MessageEntries::FilterAdd,
+            MessageEntries::QueryApply,
+            MessageEntries::RemoveLimit,
+        ];
+
+        for message in refetches {
+            let _ = entries.update(&table, message);
+
+            assert!(matches!(entries.state, RequestState::Idle));
+            assert!(entries.message.is_some());
+            assert_eq!(entries.value(1, "stock"), Some(&ColumnValue::Int(6)));
+        }
+
+        // once saved or discarded it goes ahead
+        entries.pending.clear();
+        let _ = entries.update(&table, MessageEntries::FilterRemove("stock".to_owned()));
+        assert!(matches!(entries.state, RequestState::Requesting));
+    }
+
+    #[test]
+    fn escape_keeps_the_filter_over_unsaved_edits() {
+        let table = table();
+        let mut entries = entries();
+        entries.filter.insert("stock", Comp::Eq(ColumnValue::Int(5)));
+        entries.set_pending(1, "stock".to_owned(), Some(ColumnValue::Int(6)));
+
+        let _ = entries.update(&table, MessageEntries::Escape);
+
+        assert!(!entries.filter.is_empty());
+        assert!(matches!(entries.state, RequestState::Idle));
+        assert_eq!(entries.value(1, "stock"), Some(&ColumnValue::Int(6)));
+    }
+
+    #[test]
+    fn saves_land_on_rows_moved_meanwhile() {
+        let table = table();
+        let mut entries = entries();
+        entries.set_pending(2, "stock".to_owned(), Some(ColumnValue::Int(6)));
+        let _ = entries.update(&table, MessageEntries::SaveConfirm(2));
+
+        // the first row is deleted while the save is out
+        entries.apply_change(&table, Change::Delete(ColumnValue::Int(1)));
+        entries.set_pending(0, "stock".to_owned(), Some(ColumnValue::Int(7)));
+
+        let _ = entries.update(&table, MessageEntries::UpdateResponse(ColumnValue::Int(3), Ok(())));
+
+        assert_eq!(entries.entries[1]["stock"], Some(ColumnValue::Int(6)));
+        assert!(!entries.pending.contains_key(&1));
+        assert_eq!(entries.value(0, "stock"), Some(&ColumnValue::Int(7)));
+    }
+
+    #[test]
+    fn bulk_updates_need_every_matching_row() {
+        let table = table();
+        let mut entries = entries();
+        entries.bulk_draft.column = Some("stock".to_owned());
+        entries.bulk_draft.value = "0".to_owned();
+
+        // the server has more rows than it sent
+        entries.total = Some(10);
+        let _ = entries.update(&table, MessageEntries::BulkConfirm);
+        assert!(!entries.bulk_draft.confirm);
+        assert_eq!(entries.bulk_draft.error.as_deref(), Some("only 3 of 10 matching rows are loaded, narrow the filter or load every row first"));
+
+        entries.total = Some(3);
+        entries.bulk_draft.error = None;
+        let _ = entries.update(&table, MessageEntries::BulkConfirm);
+        assert!(entries.bulk_draft.confirm);
+        assert_eq!(entries.bulk_rows(&table).map(|rows| rows.len()), Ok(3));
     }
 }
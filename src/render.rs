@@ -0,0 +1,118 @@
+use crate::settings::Settings;
+use crate::table::{ColumnType, ColumnValue, TableColumn, TableColumnForeignKey};
+
+// how the cells of a column are turned into text, picked once per column
+#[derive(Debug, Clone, Copy)]
+pub enum ColumnRenderer<'a> {
+    Number,
+    Bool,
+    Text,
+    Json,
+    // a key into another table, shown as a number with a link to the referenced row
+    Reference(&'a TableColumnForeignKey),
+}
+
+impl<'a> ColumnRenderer<'a> {
+    pub fn for_column(column: &'a TableColumn) -> Self {
+        if let Some(key) = column.foreign_keys.first() {
+            return ColumnRenderer::Reference(key);
+        }
+
+        match column.ty {
+            ColumnType::Int | ColumnType::Float => ColumnRenderer::Number,
+            ColumnType::Bool => ColumnRenderer::Bool,
+            ColumnType::String => ColumnRenderer::Text,
+            ColumnType::Json => ColumnRenderer::Json,
+        }
+    }
+
+    pub fn format(&self, value: &ColumnValue, settings: &Settings) -> String {
+        match (self, value) {
+            (ColumnRenderer::Number | ColumnRenderer::Reference(_), ColumnValue::Int(value)) => {
+                settings.number_format.format_int(*value)
+            },
+            (ColumnRenderer::Number | ColumnRenderer::Reference(_), ColumnValue::Float(value)) => {
+                settings.number_format.format_float(*value)
+            },
+            (ColumnRenderer::Number | ColumnRenderer::Reference(_), ColumnValue::Big(value)) => {
+                settings.number_format.format_digits(&value.to_string())
+            },
+            (_, value) => value.to_string(),
+        }
+    }
+
+    pub fn reference(&self) -> Option<&'a TableColumnForeignKey> {
+        match self {
+            ColumnRenderer::Reference(key) => Some(key),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::NumberFormat;
+
+    fn column(ty: ColumnType, reference: bool) -> TableColumn {
+        let foreign_keys = if reference {
+            vec![TableColumnForeignKey { table: "item".to_owned(), column: "id".to_owned() }]
+        }
+        else {
+            Vec::new()
+        };
+
+        TableColumn {
+            name: "value".to_owned(),
+            ty,
+            optional: false,
+            primary_key: false,
+            foreign_keys,
+            mapper: None,
+            values: None,
+            read_only: false,
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings { number_format: NumberFormat::Comma, ..Settings::default() }
+    }
+
+    #[test]
+    fn renderer_follows_the_column() {
+        assert!(matches!(ColumnRenderer::for_column(&column(ColumnType::Int, false)), ColumnRenderer::Number));
+        assert!(matches!(ColumnRenderer::for_column(&column(ColumnType::Float, false)), ColumnRenderer::Number));
+        assert!(matches!(ColumnRenderer::for_column(&column(ColumnType::Bool, false)), ColumnRenderer::Bool));
+        assert!(matches!(ColumnRenderer::for_column(&column(ColumnType::String, false)), ColumnRenderer::Text));
+        assert!(matches!(ColumnRenderer::for_column(&column(ColumnType::Json, false)), ColumnRenderer::Json));
+
+        // a key wins over the type
+        let key = column(ColumnType::Int, true);
+        assert_eq!(ColumnRenderer::for_column(&key).reference().map(|key| key.table.as_str()), Some("item"));
+        assert!(ColumnRenderer::for_column(&column(ColumnType::Int, false)).reference().is_none());
+    }
+
+    #[test]
+    fn numbers_are_grouped() {
+        let settings = settings();
+        let key = column(ColumnType::Int, true);
+
+        for renderer in [ColumnRenderer::Number, ColumnRenderer::for_column(&key)] {
+            assert_eq!(renderer.format(&ColumnValue::Int(-1234567), &settings), "-1,234,567");
+            assert_eq!(renderer.format(&ColumnValue::Float(1234.5), &settings), "1,234.5");
+            assert_eq!(renderer.format(&ColumnValue::Big("12345678901234567890".parse().unwrap()), &settings), "12,345,678,901,234,567,890");
+        }
+    }
+
+    #[test]
+    fn other_values_read_as_they_are() {
+        let settings = settings();
+
+        assert_eq!(ColumnRenderer::Bool.format(&ColumnValue::Bool(true), &settings), "true");
+        assert_eq!(ColumnRenderer::Text.format(&ColumnValue::from("1234"), &settings), "1234");
+        assert_eq!(ColumnRenderer::Json.format(&ColumnValue::Json(serde_json::json!({"a": 1234})), &settings), r#"{"a":1234}"#);
+
+        // a number in a text column isn't grouped
+        assert_eq!(ColumnRenderer::Text.format(&ColumnValue::Int(1234), &settings), "1234");
+    }
+}
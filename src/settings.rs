@@ -0,0 +1,939 @@
+use std::collections::HashMap;
+use std::iter;
+use std::path::PathBuf;
+
+const HISTORY_LENGTH: usize = 10;
+const MAX_ROWS: usize = 5000;
+const FONT_SIZES: [u16; 6] = [10, 12, 14, 16, 18, 20];
+const REQUEST_LIMITS: [usize; 5] = [1, 2, 4, 8, 16];
+
+use iced::{Alignment, Color, Element, Font, Length};
+use iced::widget::{button, checkbox, column, pick_list, row, text, text_input};
+
+use crate::api::{self, Filter, FilterTransport};
+use crate::table::{ColumnType, Table, TableColumn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum NumberFormat {
+    Plain,
+    Comma,
+    Period,
+    Space,
+}
+
+impl NumberFormat {
+    pub const ALL: [NumberFormat; 4] = [
+        NumberFormat::Plain,
+        NumberFormat::Comma,
+        NumberFormat::Period,
+        NumberFormat::Space,
+    ];
+
+    // guess from the posix locale variables, falling back to no grouping
+    pub fn detect() -> Self {
+        let locale = ["LC_ALL", "LC_NUMERIC", "LANG"].into_iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty());
+
+        let Some(locale) = locale else { return NumberFormat::Plain; };
+        let language = locale.split(['_', '-', '.', '@']).next().unwrap_or_default();
+
+        match language {
+            "en" | "ja" | "ko" | "zh" | "he" | "th" => NumberFormat::Comma,
+            "de" | "da" | "es" | "id" | "it" | "nl" | "pt" | "tr" | "el" => NumberFormat::Period,
+            "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "uk" | "hu" => NumberFormat::Space,
+            _ => NumberFormat::Plain,
+        }
+    }
+
+    fn separators(&self) -> (Option<char>, char) {
+        match self {
+            NumberFormat::Plain => (None, '.'),
+            NumberFormat::Comma => (Some(','), '.'),
+            NumberFormat::Period => (Some('.'), ','),
+            NumberFormat::Space => (Some(' '), ','),
+        }
+    }
+
+    pub fn format_int(&self, value: i64) -> String {
+        self.format_number(&value.to_string())
+    }
+
+    pub fn format_float(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+
+        self.format_number(&value.to_string())
+    }
+
+    // digits that don't fit a primitive, exponents are left as they are
+    pub fn format_digits(&self, number: &str) -> String {
+        if number.contains(['e', 'E']) {
+            return number.to_owned();
+        }
+
+        self.format_number(number)
+    }
+
+    fn format_number(&self, number: &str) -> String {
+        let (grouping, decimal) = self.separators();
+
+        let (sign, number) = match number.strip_prefix('-') {
+            Some(number) => ("-", number),
+            None => ("", number),
+        };
+
+        let (integer, fraction) = match number.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (number, None),
+        };
+
+        let mut formatted = sign.to_owned();
+
+        // insert a separator before every group of three digits from the right
+        for (i, digit) in integer.chars().enumerate() {
+            if let Some(grouping) = grouping {
+                if i > 0 && (integer.len() - i) % 3 == 0 {
+                    formatted.push(grouping);
+                }
+            }
+            formatted.push(digit);
+        }
+
+        if let Some(fraction) = fraction {
+            formatted.push(decimal);
+            formatted.push_str(fraction);
+        }
+
+        formatted
+    }
+}
+
+impl std::fmt::Display for NumberFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.format_float(1234.56))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum Density {
+    Compact,
+    #[default]
+    Comfortable,
+    Spacious,
+}
+
+impl Density {
+    pub const ALL: [Density; 3] = [
+        Density::Compact,
+        Density::Comfortable,
+        Density::Spacious,
+    ];
+
+    // the one after this, wrapping around
+    pub fn next(&self) -> Density {
+        match self {
+            Density::Compact => Density::Comfortable,
+            Density::Comfortable => Density::Spacious,
+            Density::Spacious => Density::Compact,
+        }
+    }
+
+    pub fn text_size(&self) -> u16 {
+        match self {
+            Density::Compact => 12,
+            Density::Comfortable => 16,
+            Density::Spacious => 16,
+        }
+    }
+
+    // vertical padding around each cell
+    pub fn padding(&self) -> u16 {
+        match self {
+            Density::Compact => 0,
+            Density::Comfortable => 0,
+            Density::Spacious => 6,
+        }
+    }
+}
+
+impl std::fmt::Display for Density {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Density::Compact => "Compact",
+            Density::Comfortable => "Comfortable",
+            Density::Spacious => "Spacious",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum TableOrder {
+    #[default]
+    Schema,
+    Alphabetical,
+}
+
+impl TableOrder {
+    pub const ALL: [TableOrder; 2] = [
+        TableOrder::Schema,
+        TableOrder::Alphabetical,
+    ];
+}
+
+impl std::fmt::Display for TableOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            TableOrder::Schema => "Schema",
+            TableOrder::Alphabetical => "Alphabetical",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum GridFont {
+    #[default]
+    Proportional,
+    // numbers and keys only
+    MonospaceNumbers,
+    Monospace,
+}
+
+impl GridFont {
+    pub const ALL: [GridFont; 3] = [
+        GridFont::Proportional,
+        GridFont::MonospaceNumbers,
+        GridFont::Monospace,
+    ];
+
+    pub fn font_for(&self, column: &TableColumn) -> Font {
+        let numeric = matches!(column.ty, ColumnType::Int | ColumnType::Float)
+            || column.primary_key
+            || !column.foreign_keys.is_empty();
+
+        match self {
+            GridFont::Monospace => Font::MONOSPACE,
+            GridFont::MonospaceNumbers if numeric => Font::MONOSPACE,
+            _ => Font::DEFAULT,
+        }
+    }
+}
+
+impl std::fmt::Display for GridFont {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            GridFont::Proportional => "Proportional",
+            GridFont::MonospaceNumbers => "Monospace numbers and ids",
+            GridFont::Monospace => "Monospace",
+        })
+    }
+}
+
+// how a null cell reads, so it isn't mistaken for an empty string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum NullDisplay {
+    #[default]
+    Blank,
+    Symbol,
+    Word,
+}
+
+impl NullDisplay {
+    pub const ALL: [NullDisplay; 3] = [
+        NullDisplay::Blank,
+        NullDisplay::Symbol,
+        NullDisplay::Word,
+    ];
+
+    pub fn text(&self) -> &'static str {
+        match self {
+            NullDisplay::Blank => "",
+            NullDisplay::Symbol => "∅",
+            NullDisplay::Word => "(null)",
+        }
+    }
+
+    // per column overrides cycle back to following the global setting
+    pub fn next(current: Option<NullDisplay>) -> Option<NullDisplay> {
+        match current {
+            None => Some(NullDisplay::Blank),
+            Some(NullDisplay::Blank) => Some(NullDisplay::Symbol),
+            Some(NullDisplay::Symbol) => Some(NullDisplay::Word),
+            Some(NullDisplay::Word) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for NullDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            NullDisplay::Blank => "Blank",
+            NullDisplay::Symbol => "∅",
+            NullDisplay::Word => "(null)",
+        })
+    }
+}
+
+// what a null cell turns into when a whole column is copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum NullCopy {
+    #[default]
+    EmptyLine,
+    Skip,
+}
+
+impl NullCopy {
+    pub const ALL: [NullCopy; 2] = [
+        NullCopy::EmptyLine,
+        NullCopy::Skip,
+    ];
+}
+
+impl std::fmt::Display for NullCopy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            NullCopy::EmptyLine => "Empty lines",
+            NullCopy::Skip => "Skipped",
+        })
+    }
+}
+
+// marks which environment is connected, so production stands out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum EnvironmentColor {
+    #[default]
+    Green,
+    Blue,
+    Yellow,
+    Red,
+}
+
+impl EnvironmentColor {
+    pub const ALL: [EnvironmentColor; 4] = [
+        EnvironmentColor::Green,
+        EnvironmentColor::Blue,
+        EnvironmentColor::Yellow,
+        EnvironmentColor::Red,
+    ];
+
+    pub fn color(&self) -> Color {
+        match self {
+            EnvironmentColor::Green => Color::from_rgb8(0x3f, 0xa3, 0x4d),
+            EnvironmentColor::Blue => Color::from_rgb8(0x3b, 0x82, 0xf6),
+            EnvironmentColor::Yellow => Color::from_rgb8(0xea, 0xb3, 0x08),
+            EnvironmentColor::Red => Color::from_rgb8(0xdc, 0x26, 0x26),
+        }
+    }
+}
+
+impl std::fmt::Display for EnvironmentColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            EnvironmentColor::Green => "Green",
+            EnvironmentColor::Blue => "Blue",
+            EnvironmentColor::Yellow => "Yellow",
+            EnvironmentColor::Red => "Red",
+        })
+    }
+}
+
+// a named backend to connect to, the token is stored as plain text like the rest of the settings
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Environment {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default)]
+    pub color: EnvironmentColor,
+}
+
+impl std::fmt::Display for Environment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+// a pick list entry for the grid text size, none follows the density
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FontSize(Option<u16>);
+
+impl std::fmt::Display for FontSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(size) => write!(f, "{}", size),
+            None => f.write_str("Density default"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    // numbers read best right-aligned
+    pub fn default_for(ty: ColumnType) -> Self {
+        match ty {
+            ColumnType::Int | ColumnType::Float => Align::Right,
+            _ => Align::Left,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Align::Left => "Left aligned, click to change",
+            Align::Center => "Centered, click to change",
+            Align::Right => "Right aligned, click to change",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Align::Left => Align::Center,
+            Align::Center => Align::Right,
+            Align::Right => Align::Left,
+        }
+    }
+}
+
+impl From<Align> for Alignment {
+    fn from(align: Align) -> Self {
+        match align {
+            Align::Left => Alignment::Start,
+            Align::Center => Alignment::Center,
+            Align::Right => Alignment::End,
+        }
+    }
+}
+
+impl std::fmt::Display for Align {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Align::Left => "L",
+            Align::Center => "C",
+            Align::Right => "R",
+        })
+    }
+}
+
+// overrides for how a single column is displayed, unset alignment follows the column type
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ColumnDisplay {
+    pub align: Option<Align>,
+    pub wrap: bool,
+    // unset follows the global null display
+    pub null: Option<NullDisplay>,
+    // kept on the left while the other columns scroll sideways
+    pub pinned: bool,
+}
+
+// a table with its filter and view options, saved under a name to be opened again in one click
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub table: String,
+    pub filter: Filter,
+    // only the key and audit columns are shown
+    #[serde(default)]
+    pub provenance: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub number_format: NumberFormat,
+    pub density: Density,
+    pub table_order: TableOrder,
+    pub sidebar_collapsed: bool,
+    pub history: Vec<String>,
+    // soft cap on rows fetched per query, none fetches everything
+    pub max_rows: Option<usize>,
+    pub filter_transport: FilterTransport,
+    // table name -> column name -> display overrides
+    pub columns: HashMap<String, HashMap<String, ColumnDisplay>>,
+    // tables listed first in the sidebar, families are pinned by their base
+    pub pinned: Vec<String>,
+    pub grid_font: GridFont,
+    // overrides the density's text size in the entries grid
+    pub grid_font_size: Option<u16>,
+    pub null_display: NullDisplay,
+    // requests the client runs at once, the rest wait their turn
+    pub max_requests: usize,
+    // seconds a request may take, none waits as long as the connection stays up
+    pub request_timeout: Option<u64>,
+    // megabytes of a response read before giving up on it, none reads any size
+    pub max_response: Option<usize>,
+    // table name -> name shown instead of the derived one
+    pub aliases: HashMap<String, String>,
+    pub environments: Vec<Environment>,
+    pub null_copy: NullCopy,
+    // connect on launch to the environment, or else the url, used last
+    pub auto_connect: bool,
+    pub last_environment: Option<String>,
+    // follow the server's change stream for the open table, where it has one
+    pub live_updates: bool,
+    // reconnecting to the same backend picks up the open table and its rows where they were
+    pub keep_view: bool,
+    // characters of each request body written to stderr, none logs nothing
+    pub log_bodies: Option<usize>,
+    // named views listed in the sidebar, in the order they were saved
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            number_format: NumberFormat::detect(),
+            density: Density::default(),
+            table_order: TableOrder::default(),
+            sidebar_collapsed: false,
+            history: Vec::new(),
+            max_rows: Some(MAX_ROWS),
+            filter_transport: FilterTransport::default(),
+            columns: HashMap::new(),
+            pinned: Vec::new(),
+            grid_font: GridFont::default(),
+            grid_font_size: None,
+            null_display: NullDisplay::default(),
+            max_requests: api::MAX_REQUESTS,
+            request_timeout: Some(api::TIMEOUT),
+            max_response: Some(api::MAX_RESPONSE),
+            aliases: HashMap::new(),
+            environments: Vec::new(),
+            null_copy: NullCopy::default(),
+            auto_connect: false,
+            last_environment: None,
+            live_updates: false,
+            keep_view: false,
+            log_bodies: None,
+            bookmarks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsError {
+    #[error("no config directory")]
+    NoConfigDir,
+    #[error("io error: `{0}`")]
+    Io(#[from] std::io::Error),
+    #[error("json error: `{0}`")]
+    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    Invalid(&'static str),
+}
+
+impl Settings {
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("gameshopui").join("settings.json"))
+    }
+
+    // missing or unreadable settings fall back to the defaults
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    // most recent first
+    pub fn remember_url(&mut self, url: &str) -> Result<(), SettingsError> {
+        self.history.retain(|entry| entry != url);
+        self.history.insert(0, url.to_owned());
+        self.history.truncate(HISTORY_LENGTH);
+
+        self.save()
+    }
+
+    // replaces an environment of the same name
+    pub fn save_environment(&mut self, environment: Environment) -> Result<(), SettingsError> {
+        match self.environments.iter_mut().find(|entry| entry.name == environment.name) {
+            Some(entry) => *entry = environment,
+            None => self.environments.push(environment),
+        }
+
+        self.save()
+    }
+
+    pub fn forget_environment(&mut self, name: &str) -> Result<(), SettingsError> {
+        self.environments.retain(|entry| entry.name != name);
+
+        self.save()
+    }
+
+    // where to connect on launch, when auto-connect is on and something was connected to before
+    pub fn launch_target(&self) -> Option<Environment> {
+        if !self.auto_connect {
+            return None;
+        }
+
+        let environment = self.last_environment.as_deref()
+            .and_then(|name| self.environment(name))
+            .cloned();
+
+        environment.or_else(|| {
+            self.history.first().map(|url| Environment {
+                name: String::new(),
+                url: url.clone(),
+                token: None,
+                prefix: String::new(),
+                color: EnvironmentColor::default(),
+            })
+        })
+    }
+
+    pub fn environment(&self, name: &str) -> Option<&Environment> {
+        self.environments.iter().find(|entry| entry.name == name)
+    }
+
+    // the environment a client was set up from, if any
+    pub fn environment_of(&self, client: &api::Client) -> Option<&Environment> {
+        self.environments.iter().find(|entry| {
+            api::Client::normalize_url(&entry.url).is_ok_and(|url| url == client.url)
+                && entry.prefix == client.prefix
+                && entry.token == client.token
+        })
+    }
+
+    pub fn forget_url(&mut self, url: &str) -> Result<(), SettingsError> {
+        self.history.retain(|entry| entry != url);
+
+        self.save()
+    }
+
+    fn column_display(&self, table: &str, column: &str) -> ColumnDisplay {
+        self.columns.get(table)
+            .and_then(|columns| columns.get(column))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn align(&self, table: &str, column: &TableColumn) -> Align {
+        self.column_display(table, &column.name).align
+            .unwrap_or_else(|| Align::default_for(column.ty))
+    }
+
+    pub fn wrap(&self, table: &str, column: &str) -> bool {
+        self.column_display(table, column).wrap
+    }
+
+    pub fn column_pinned(&self, table: &str, column: &str) -> bool {
+        self.column_display(table, column).pinned
+    }
+
+    pub fn column_null(&self, table: &str, column: &str) -> Option<NullDisplay> {
+        self.column_display(table, column).null
+    }
+
+    pub fn null_display(&self, table: &str, column: &str) -> NullDisplay {
+        self.column_null(table, column).unwrap_or(self.null_display)
+    }
+
+    pub fn grid_text_size(&self) -> u16 {
+        self.grid_font_size.unwrap_or_else(|| self.density.text_size())
+    }
+
+    // the alias if one is set, otherwise the name derived from the table
+    pub fn table_name(&self, table: &Table) -> String {
+        self.aliases.get(&table.table)
+            .cloned()
+            .unwrap_or_else(|| table.pretty_name())
+    }
+
+    pub fn is_pinned(&self, table: &str) -> bool {
+        self.pinned.iter().any(|pinned| pinned == table)
+    }
+
+    // back to first-run state, the file is removed rather than rewritten with defaults
+    pub fn reset(&mut self) -> Result<(), SettingsError> {
+        *self = Self::default();
+
+        let path = Self::path().ok_or(SettingsError::NoConfigDir)?;
+
+        match std::fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), SettingsError> {
+        let path = Self::path().ok_or(SettingsError::NoConfigDir)?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let text = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, text)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MessageSettings {
+    NumberFormat(NumberFormat),
+    Density(Density),
+    TableOrder(TableOrder),
+    SidebarCollapsed(bool),
+    MaxRows(String),
+    MaxRequests(usize),
+    RequestTimeout(String),
+    MaxResponse(String),
+    FilterTransport(FilterTransport),
+    ColumnAlign(String, String, Align),
+    ColumnWrap(String, String, bool),
+    ColumnPin(String, String, bool),
+    Pin(String, bool),
+    GridFont(GridFont),
+    GridFontSize(FontSize),
+    NullDisplay(NullDisplay),
+    ColumnNull(String, String, Option<NullDisplay>),
+    Alias(String, String),
+    NullCopy(NullCopy),
+    AutoConnect(bool),
+    LiveUpdates(bool),
+    KeepView(bool),
+    LogBodies(String),
+    Bookmark(Bookmark),
+    ForgetBookmark(String),
+    Reset,
+}
+
+impl Settings {
+    pub fn update(&mut self, message: MessageSettings) -> Result<(), SettingsError> {
+        match message {
+            MessageSettings::NumberFormat(format) => self.number_format = format,
+            MessageSettings::Density(density) => self.density = density,
+            MessageSettings::TableOrder(order) => self.table_order = order,
+            MessageSettings::SidebarCollapsed(collapsed) => self.sidebar_collapsed = collapsed,
+            MessageSettings::MaxRows(rows) if rows.is_empty() => self.max_rows = None,
+            MessageSettings::MaxRows(rows) => match rows.parse() {
+                Ok(rows) => self.max_rows = Some(rows),
+                Err(_) => return Ok(()),
+            },
+            MessageSettings::MaxRequests(requests) => self.max_requests = requests,
+            MessageSettings::RequestTimeout(seconds) if seconds.is_empty() => self.request_timeout = None,
+            MessageSettings::RequestTimeout(seconds) => match seconds.parse() {
+                // no request could finish in no time at all
+                Ok(0) => return Err(SettingsError::Invalid("the request timeout must be at least 1 second, leave it empty to wait without a limit")),
+                Ok(seconds) => self.request_timeout = Some(seconds),
+                Err(_) => return Ok(()),
+            },
+            MessageSettings::MaxResponse(megabytes) if megabytes.is_empty() => self.max_response = None,
+            MessageSettings::MaxResponse(megabytes) => match megabytes.parse() {
+                Ok(megabytes) => self.max_response = Some(megabytes),
+                Err(_) => return Ok(()),
+            },
+            MessageSettings::FilterTransport(transport) => self.filter_transport = transport,
+            MessageSettings::ColumnAlign(table, column, align) => {
+                self.columns.entry(table).or_default().entry(column).or_default().align = Some(align);
+            },
+            MessageSettings::ColumnWrap(table, column, wrap) => {
+                self.columns.entry(table).or_default().entry(column).or_default().wrap = wrap;
+            },
+            MessageSettings::ColumnPin(table, column, pinned) => {
+                self.columns.entry(table).or_default().entry(column).or_default().pinned = pinned;
+            },
+            MessageSettings::Pin(table, pinned) => {
+                self.pinned.retain(|entry| *entry != table);
+                if pinned {
+                    self.pinned.push(table);
+                }
+            },
+            MessageSettings::GridFont(font) => self.grid_font = font,
+            MessageSettings::GridFontSize(FontSize(size)) => self.grid_font_size = size,
+            MessageSettings::NullDisplay(null) => self.null_display = null,
+            MessageSettings::NullCopy(copy) => self.null_copy = copy,
+            MessageSettings::AutoConnect(enabled) => self.auto_connect = enabled,
+            MessageSettings::LiveUpdates(enabled) => self.live_updates = enabled,
+            MessageSettings::KeepView(enabled) => self.keep_view = enabled,
+            // saving under a name that's taken replaces that bookmark
+            MessageSettings::Bookmark(bookmark) => {
+                self.bookmarks.retain(|existing| existing.name != bookmark.name);
+                self.bookmarks.push(bookmark);
+            },
+            MessageSettings::ForgetBookmark(name) => self.bookmarks.retain(|bookmark| bookmark.name != name),
+            MessageSettings::LogBodies(length) if length.is_empty() => self.log_bodies = None,
+            MessageSettings::LogBodies(length) => match length.parse() {
+                Ok(length) => self.log_bodies = Some(length),
+                Err(_) => return Ok(()),
+            },
+            MessageSettings::ColumnNull(table, column, null) => {
+                self.columns.entry(table).or_default().entry(column).or_default().null = null;
+            },
+            // a blank alias goes back to the derived name
+            MessageSettings::Alias(table, alias) => match alias.trim() {
+                "" => { self.aliases.remove(&table); },
+                alias => { self.aliases.insert(table, alias.to_owned()); },
+            },
+            // confirmed by the parent first
+            MessageSettings::Reset => unreachable!(),
+        }
+
+        self.save()
+    }
+
+    pub fn view(&self) -> Element<MessageSettings> {
+        let number_format = row![
+            text("Number format").width(Length::Fill),
+            pick_list(NumberFormat::ALL, Some(self.number_format), MessageSettings::NumberFormat),
+        ]
+        .spacing(8);
+
+        let density = row![
+            text("Density").width(Length::Fill),
+            pick_list(Density::ALL, Some(self.density), MessageSettings::Density),
+        ]
+        .spacing(8);
+
+        let grid_font = row![
+            text("Grid font").width(Length::Fill),
+            pick_list(GridFont::ALL, Some(self.grid_font), MessageSettings::GridFont),
+        ]
+        .spacing(8);
+
+        let sizes: Vec<_> = iter::once(FontSize(None))
+            .chain(FONT_SIZES.into_iter().map(|size| FontSize(Some(size))))
+            .collect();
+
+        let grid_font_size = row![
+            text("Grid font size").width(Length::Fill),
+            pick_list(sizes, Some(FontSize(self.grid_font_size)), MessageSettings::GridFontSize),
+        ]
+        .spacing(8);
+
+        let null_display = row![
+            text("Show nulls as").width(Length::Fill),
+            pick_list(NullDisplay::ALL, Some(self.null_display), MessageSettings::NullDisplay),
+        ]
+        .spacing(8);
+
+        let null_copy = row![
+            text("Copied columns put nulls as").width(Length::Fill),
+            pick_list(NullCopy::ALL, Some(self.null_copy), MessageSettings::NullCopy),
+        ]
+        .spacing(8);
+
+        let table_order = row![
+            text("Table order").width(Length::Fill),
+            pick_list(TableOrder::ALL, Some(self.table_order), MessageSettings::TableOrder),
+        ]
+        .spacing(8);
+
+        let max_rows = self.max_rows
+            .map(|rows| rows.to_string())
+            .unwrap_or_default();
+
+        let max_rows = row![
+            text("Row limit").width(Length::Fill),
+            text_input("none", &max_rows)
+                .on_input(MessageSettings::MaxRows)
+                .width(120),
+        ]
+        .spacing(8);
+
+        let max_requests = row![
+            text("Concurrent requests").width(Length::Fill),
+            pick_list(REQUEST_LIMITS, Some(self.max_requests), MessageSettings::MaxRequests),
+        ]
+        .spacing(8);
+
+        let request_timeout = self.request_timeout
+            .map(|seconds| seconds.to_string())
+            .unwrap_or_default();
+
+        let request_timeout = row![
+            text("Request timeout, seconds").width(Length::Fill),
+            text_input("none", &request_timeout)
+                .on_input(MessageSettings::RequestTimeout)
+                .width(120),
+        ]
+        .spacing(8);
+
+        let max_response = self.max_response
+            .map(|megabytes| megabytes.to_string())
+            .unwrap_or_default();
+
+        let max_response = row![
+            text("Response size limit, MB").width(Length::Fill),
+            text_input("none", &max_response)
+                .on_input(MessageSettings::MaxResponse)
+                .width(120),
+        ]
+        .spacing(8);
+
+        let filter_transport = row![
+            text("Send filters as").width(Length::Fill),
+            pick_list(FilterTransport::ALL, Some(self.filter_transport), MessageSettings::FilterTransport),
+        ]
+        .spacing(8);
+
+        let log_bodies = self.log_bodies
+            .map(|length| length.to_string())
+            .unwrap_or_default();
+
+        let log_bodies = row![
+            text("Log request bodies, characters").width(Length::Fill),
+            text_input("off", &log_bodies)
+                .on_input(MessageSettings::LogBodies)
+                .width(120),
+        ]
+        .spacing(8);
+
+        let auto_connect = checkbox("Connect to the last backend on launch", self.auto_connect)
+            .on_toggle(MessageSettings::AutoConnect);
+
+        let keep_view = checkbox("Keep the open table when reconnecting to the same backend", self.keep_view)
+            .on_toggle(MessageSettings::KeepView);
+
+        column![
+            text("Settings").size(24),
+            number_format,
+            density,
+            grid_font,
+            grid_font_size,
+            null_display,
+            null_copy,
+            table_order,
+            max_rows,
+            max_requests,
+            request_timeout,
+            max_response,
+            filter_transport,
+            log_bodies,
+            auto_connect,
+            keep_view,
+            button(text("Reset all settings"))
+                .on_press(MessageSettings::Reset)
+                .style(button::danger),
+        ]
+        .spacing(16)
+        .padding(16)
+        .max_width(512)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_request_timeout_is_refused() {
+        let mut settings = Settings { request_timeout: Some(30), ..Settings::default() };
+
+        let result = settings.update(MessageSettings::RequestTimeout("0".to_owned()));
+
+        assert!(matches!(result, Err(SettingsError::Invalid(_))));
+        assert_eq!(settings.request_timeout, Some(30));
+    }
+}
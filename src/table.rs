@@ -1,9 +1,9 @@
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
-use serde_json::{Number, Value};
+use serde_json::{Map, Number, Value};
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum ColumnType {
     #[serde(rename = "bool")]
     Bool,
@@ -13,6 +13,20 @@ pub enum ColumnType {
     Float,
     #[serde(rename = "str")]
     String,
+    #[serde(rename = "json")]
+    Json,
+}
+
+impl std::fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ColumnType::Bool => "bool",
+            ColumnType::Int => "int",
+            ColumnType::Float => "float",
+            ColumnType::String => "string",
+            ColumnType::Json => "json",
+        })
+    }
 }
 
 #[allow(dead_code)]
@@ -21,7 +35,10 @@ pub enum ColumnValue {
     Bool(bool),
     Int(i64),
     Float(f64),
+    // a number an i64 or f64 can't hold without losing digits, kept as the server sent it
+    Big(Number),
     String(String),
+    Json(Value),
 }
 
 impl PartialEq for ColumnValue {
@@ -30,7 +47,9 @@ impl PartialEq for ColumnValue {
             (Self::Bool(a), Self::Bool(b)) => a == b,
             (Self::Int(a), Self::Int(b)) => a == b,
             (Self::Float(a), Self::Float(b)) => a == b,
+            (Self::Big(a), Self::Big(b)) => a == b,
             (Self::String(a), Self::String(b)) => a == b,
+            (Self::Json(a), Self::Json(b)) => a == b,
             _ => false,
         }
     }
@@ -43,8 +62,27 @@ impl From<ColumnValue> for Value {
         match value {
             ColumnValue::Bool(value) => Value::Bool(value),
             ColumnValue::Int(value) => Value::Number(Number::from(value)),
-            ColumnValue::Float(value) => Value::Number(Number::from_f64(value).unwrap()),
+            ColumnValue::Float(value) => Number::from_f64(value).map_or(Value::Null, Value::Number),
+            ColumnValue::Big(value) => Value::Number(value),
             ColumnValue::String(value) => Value::String(value),
+            ColumnValue::Json(value) => value,
+        }
+    }
+}
+
+// the same json `Value::from` gives, without building the value first
+impl serde::Serialize for ColumnValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer
+    {
+        match self {
+            ColumnValue::Bool(value) => serializer.serialize_bool(*value),
+            ColumnValue::Int(value) => serializer.serialize_i64(*value),
+            ColumnValue::Float(value) => serializer.serialize_f64(*value),
+            ColumnValue::Big(value) => value.serialize(serializer),
+            ColumnValue::String(value) => serializer.serialize_str(value),
+            ColumnValue::Json(value) => value.serialize(serializer),
         }
     }
 }
@@ -55,20 +93,148 @@ impl std::fmt::Display for ColumnValue {
             ColumnValue::Bool(value) => value.to_string(),
             ColumnValue::Int(value) => value.to_string(),
             ColumnValue::Float(value) => value.to_string(),
+            ColumnValue::Big(value) => value.to_string(),
             ColumnValue::String(value) => value.to_string(),
+            ColumnValue::Json(value) => value.to_string(),
         };
 
         f.write_str(&string)
     }
 }
 
+impl From<bool> for ColumnValue {
+    fn from(value: bool) -> Self {
+        ColumnValue::Bool(value)
+    }
+}
+
+impl From<i64> for ColumnValue {
+    fn from(value: i64) -> Self {
+        ColumnValue::Int(value)
+    }
+}
+
+impl From<i32> for ColumnValue {
+    fn from(value: i32) -> Self {
+        ColumnValue::Int(value.into())
+    }
+}
+
+impl From<f64> for ColumnValue {
+    fn from(value: f64) -> Self {
+        ColumnValue::Float(value)
+    }
+}
+
+impl From<String> for ColumnValue {
+    fn from(value: String) -> Self {
+        ColumnValue::String(value)
+    }
+}
+
+impl From<&str> for ColumnValue {
+    fn from(value: &str) -> Self {
+        ColumnValue::String(value.to_owned())
+    }
+}
+
 impl ColumnValue {
+    // the value as the declared type when nothing is lost on the way, numbers only, anything else is kept as it is,
+    // a value that would lose precision comes back as the error
+    pub fn coerce(self, ty: ColumnType) -> Result<ColumnValue, ColumnValue> {
+        // integers beyond this don't all have an exact float
+        const EXACT: u64 = 1 << f64::MANTISSA_DIGITS;
+
+        match (self, ty) {
+            (ColumnValue::Float(value), ColumnType::Int) => {
+                if value.fract() == 0.0 && value.abs() < i64::MAX as f64 { Ok(ColumnValue::Int(value as i64)) }
+                else { Err(ColumnValue::Float(value)) }
+            },
+            (ColumnValue::Int(value), ColumnType::Float) => {
+                if value.unsigned_abs() <= EXACT { Ok(ColumnValue::Float(value as f64)) }
+                else { Err(ColumnValue::Int(value)) }
+            },
+            // whole big numbers are sent as their digits, the server decides if they fit
+            (ColumnValue::Big(value), ColumnType::Int) => {
+                if ColumnValue::is_whole(&value) { Ok(ColumnValue::Big(value)) }
+                else { Err(ColumnValue::Big(value)) }
+            },
+            (value, _) => Ok(value),
+        }
+    }
+
     pub fn ty(&self) -> ColumnType {
         match self {
             ColumnValue::Bool(_) => ColumnType::Bool,
             ColumnValue::Int(_) => ColumnType::Int,
             ColumnValue::Float(_) => ColumnType::Float,
+            ColumnValue::Big(value) if ColumnValue::is_whole(value) => ColumnType::Int,
+            ColumnValue::Big(_) => ColumnType::Float,
             ColumnValue::String(_) => ColumnType::String,
+            ColumnValue::Json(_) => ColumnType::Json,
+        }
+    }
+
+    // the narrowest variant that holds the number exactly, decimals with more digits than an f64 keeps stay big
+    pub fn from_number(number: Number) -> ColumnValue {
+        // every decimal of up to this many significant digits survives a trip through an f64
+        const DIGITS: usize = f64::DIGITS as usize;
+
+        if let Some(value) = number.as_i64() {
+            return ColumnValue::Int(value);
+        }
+
+        if ColumnValue::is_whole(&number) {
+            return ColumnValue::Big(number);
+        }
+
+        let text = number.to_string();
+        let mantissa = text.split(['e', 'E']).next().unwrap_or_default();
+        let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+        let significant = digits.trim_start_matches('0').trim_end_matches('0').len();
+
+        match number.as_f64() {
+            Some(value) if significant <= DIGITS && value.is_finite() => ColumnValue::Float(value),
+            _ => ColumnValue::Big(number),
+        }
+    }
+
+    // written without a fraction or exponent
+    fn is_whole(number: &Number) -> bool {
+        !number.to_string().contains(['.', 'e', 'E'])
+    }
+}
+
+impl ColumnValue {
+    // a starting value for a new row, date and time mappers start at the current time
+    pub fn default_for(column: &TableColumn) -> Option<ColumnValue> {
+        if let Some(now) = Self::now_for(column) {
+            return Some(now);
+        }
+
+        if column.optional {
+            return None;
+        }
+
+        let value = match column.ty {
+            ColumnType::Bool => ColumnValue::Bool(false),
+            ColumnType::Int => ColumnValue::Int(0),
+            ColumnType::Float => ColumnValue::Float(0.0),
+            ColumnType::String => ColumnValue::String(String::new()),
+            ColumnType::Json => ColumnValue::Json(Value::Object(Map::new())),
+        };
+
+        Some(value)
+    }
+
+    // the current time as a date or time column stores it, none for other columns
+    pub fn now_for(column: &TableColumn) -> Option<ColumnValue> {
+        let now = chrono::Local::now();
+
+        match (column.ty, column.time_format()) {
+            (ColumnType::String, Some(format)) => Some(ColumnValue::String(now.format(format).to_string())),
+            (ColumnType::Int, Some(_)) => Some(ColumnValue::Int(now.timestamp())),
+            _ => None,
         }
     }
 }
@@ -84,29 +250,67 @@ pub enum ColumnParseError {
 }
 
 impl ColumnValue {
-    pub fn try_from_value(value: Value) -> Result<Option<ColumnValue>, ()> {
+    // `ty` is the declared type of the column, when known, loosely typed bools are coerced to it
+    pub fn try_from_value(value: Value, ty: Option<ColumnType>) -> Result<Option<ColumnValue>, ()> {
+        if ty == Some(ColumnType::Bool) {
+            let coerced = match &value {
+                Value::Number(number) => match number.as_i64() {
+                    Some(0) => Some(false),
+                    Some(1) => Some(true),
+                    _ => None,
+                },
+                Value::String(string) => match string.as_str() {
+                    "false" | "0" => Some(false),
+                    "true" | "1" => Some(true),
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            if let Some(coerced) = coerced {
+                return Ok(Some(ColumnValue::Bool(coerced)));
+            }
+        }
+
         match value {
             Value::Null => Ok(None),
             Value::Bool(value) => Ok(Some(ColumnValue::Bool(value))),
-            Value::Number(number) => {
-                let value = if let Some(value) = number.as_f64() {
-                    ColumnValue::Float(value)
-                }
-                else if let Some(value) = number.as_i64() {
-                    ColumnValue::Int(value)
-                }
-                else {
-                    ColumnValue::Int(number.as_u64().unwrap() as i64)
-                };
+            Value::Number(number) => Ok(Some(ColumnValue::from_number(number))),
+            Value::String(value) => Ok(Some(ColumnValue::String(value))),
+            Value::Array(_) | Value::Object(_) => Ok(Some(ColumnValue::Json(value))),
+        }
+        .map(|value| value.map(|value| match ty {
+            Some(ty) => value.toward(ty),
+            None => value,
+        }))
+    }
 
-                Ok(Some(value))
+    // the value as the declared type where it reads as one without losing anything, otherwise as it came,
+    // for loosely typed backends that send e.g. `"12"` for an int column, see `is_mismatch`
+    pub fn toward(self, ty: ColumnType) -> ColumnValue {
+        match (self, ty) {
+            // only text that reads back the same, `"007"` or `" 7"` say more than the number does
+            (ColumnValue::String(text), ColumnType::Int) => match text.parse::<Number>().map(ColumnValue::from_number) {
+                Ok(value @ (ColumnValue::Int(_) | ColumnValue::Big(_))) if value.to_string() == text => value,
+                _ => ColumnValue::String(text),
             },
-            Value::String(value) => Ok(Some(ColumnValue::String(value))),
-            Value::Array(_) => Err(()),
-            Value::Object(_) => Err(()),
+            // the same for floats, `"1.50"` or `"1e3"` are written the way they are for a reason
+            (ColumnValue::String(text), ColumnType::Float) => match text.parse::<Number>().map(ColumnValue::from_number) {
+                Ok(value) if value.to_string() == text => value.coerce(ColumnType::Float).unwrap_or_else(|value| value),
+                _ => ColumnValue::String(text),
+            },
+            (value @ (ColumnValue::Bool(_) | ColumnValue::Int(_) | ColumnValue::Float(_) | ColumnValue::Big(_)), ColumnType::String) => {
+                ColumnValue::String(value.to_string())
+            },
+            (value, ty) => value.coerce(ty).unwrap_or_else(|value| value),
         }
     }
 
+    // stored as another type than the column declares, json columns hold anything
+    pub fn is_mismatch(&self, ty: ColumnType) -> bool {
+        ty != ColumnType::Json && self.ty() != ty
+    }
+
     pub fn try_from_str(column: TableColumn, value: &str) -> Result<Option<ColumnValue>, ColumnParseError> {
         if value == "" {
             return if column.optional {
@@ -122,24 +326,46 @@ impl ColumnValue {
 
         let value = match column.ty {
             ColumnType::Bool => value.parse().map(ColumnValue::Bool).map_err(|_| ColumnParseError::ParseError),
-            ColumnType::Int => value.parse().map(ColumnValue::Int).map_err(|_| ColumnParseError::ParseError),
-            ColumnType::Float => value.parse().map(ColumnValue::Float).map_err(|_| ColumnParseError::ParseError),
+            // digits past what an i64 or f64 holds are kept as typed
+            ColumnType::Int => match value.parse() {
+                Ok(value) => Ok(ColumnValue::Int(value)),
+                Err(_) => value.parse::<Number>().ok()
+                    .filter(ColumnValue::is_whole)
+                    .map(ColumnValue::from_number)
+                    .ok_or(ColumnParseError::ParseError),
+            },
+            ColumnType::Float => match value.parse() {
+                Ok(float) => match value.parse::<Number>().map(ColumnValue::from_number) {
+                    Ok(big @ ColumnValue::Big(_)) => Ok(big),
+                    _ => Ok(ColumnValue::Float(float)),
+                },
+                Err(_) => Err(ColumnParseError::ParseError),
+            },
             ColumnType::String => Ok(ColumnValue::String(value.to_owned())),
+            ColumnType::Json => serde_json::from_str(value).map(ColumnValue::Json).map_err(|_| ColumnParseError::ParseError),
         };
 
         value.map(Some)
     }
+
+    // text typed by the user, read the way the column's mapper expects when it has a known one
+    pub fn try_from_input(column: &TableColumn, text: &str) -> Result<Option<ColumnValue>, ColumnParseError> {
+        match column.known_mapper() {
+            Some(mapper) => mapper.parse(column, text),
+            None => ColumnValue::try_from_str(column.clone(), text),
+        }
+    }
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TableColumnForeignKey {
     pub table: String,
     pub column: String,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TableColumn {
     pub name: String,
     #[serde(rename = "type")]
@@ -148,10 +374,159 @@ pub struct TableColumn {
     pub primary_key: bool,
     pub foreign_keys: Vec<TableColumnForeignKey>,
     pub mapper: Option<String>,
+    // closed set of allowed values, for enum-like string columns
+    #[serde(default)]
+    pub values: Option<Vec<String>>,
+    // computed by the server, shown but never sent
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl TableColumn {
+    // values to pick from instead of typing, only string columns can be enums
+    pub fn choices(&self) -> Option<&[String]> {
+        self.values.as_deref()
+            .filter(|_| self.ty == ColumnType::String)
+    }
+
+    // date and time columns are told apart by their mapper, the format is how string columns store them
+    pub fn time_format(&self) -> Option<&'static str> {
+        let mapper = self.mapper.as_deref().unwrap_or_default().to_lowercase();
+
+        if mapper.contains("datetime") { Some("%Y-%m-%dT%H:%M:%S") }
+        else if mapper.contains("date") { Some("%Y-%m-%d") }
+        else if mapper.contains("time") { Some("%H:%M:%S") }
+        else { None }
+    }
+
+    // who or when a row was created or changed, told by the usual audit column names or an audit mapper
+    pub fn is_audit(&self) -> bool {
+        let name = self.name.to_lowercase();
+        let name = name.strip_prefix("last_").unwrap_or(&name);
+
+        let audited = ["created", "updated", "modified", "changed", "deleted"].iter()
+            .filter_map(|prefix| name.strip_prefix(prefix))
+            .any(|rest| ["", "_at", "_by", "_on", "_date", "_time", "_user"].contains(&rest));
+
+        audited || self.mapper.as_deref().is_some_and(|mapper| mapper.to_lowercase().contains("audit"))
+    }
+
+    // the mapper if it's one edits know the format of, timestamps stored as ints are edited as numbers
+    pub fn known_mapper(&self) -> Option<Mapper> {
+        let mapper = self.mapper.as_deref().unwrap_or_default().to_lowercase();
+
+        match self.ty {
+            ColumnType::String => if let Some(format) = self.time_format() {
+                return Some(Mapper::Time(format));
+            },
+            ColumnType::Int | ColumnType::Float => (),
+            _ => return None,
+        }
+
+        if mapper.contains("currency") || mapper.contains("money") { Some(Mapper::Currency) }
+        else if mapper.contains("phone") { Some(Mapper::Phone) }
+        else { None }
+    }
+}
+
+// mappers whose values are typed in a format of their own, and stored in a canonical one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mapper {
+    Currency,
+    Phone,
+    Time(&'static str),
+}
+
+impl Mapper {
+    // placeholder of an empty input
+    pub fn hint(&self) -> String {
+        match self {
+            Mapper::Currency => "12.34".to_owned(),
+            Mapper::Phone => "+1 555 123 4567".to_owned(),
+            Mapper::Time(format) => format
+                .replace("%Y", "YYYY")
+                .replace("%m", "MM")
+                .replace("%d", "DD")
+                .replace("%H", "hh")
+                .replace("%M", "mm")
+                .replace("%S", "ss"),
+        }
+    }
+
+    // drop what can't be part of a value as it's typed
+    pub fn mask(&self, text: &str) -> String {
+        let allowed = |c: char| match self {
+            Mapper::Currency => c.is_ascii_digit() || ".,-".contains(c),
+            Mapper::Phone => c.is_ascii_digit() || "+-() ".contains(c),
+            Mapper::Time(_) => c.is_ascii_digit() || "-:T ".contains(c),
+        };
+
+        text.chars().filter(|c| allowed(*c)).collect()
+    }
+
+    // how a stored value reads in the editor
+    pub fn edit_text(&self, value: &ColumnValue) -> String {
+        match (self, value) {
+            (Mapper::Currency, ColumnValue::Float(value)) => format!("{:.2}", value),
+            (_, value) => value.to_string(),
+        }
+    }
+
+    // typed text back into the column's type, in the form the server stores
+    pub fn parse(&self, column: &TableColumn, text: &str) -> Result<Option<ColumnValue>, ColumnParseError> {
+        let text = text.trim();
+
+        if text.is_empty() {
+            return ColumnValue::try_from_str(column.clone(), text);
+        }
+
+        match self {
+            Mapper::Currency => {
+                // a comma followed by one or two digits is a decimal comma, any other is a thousands separator
+                let decimal_comma = !text.contains('.')
+                    && text.rsplit_once(',').is_some_and(|(_, cents)| (1..=2).contains(&cents.len()));
+
+                let number: String = if decimal_comma { text.replace(',', ".") } else { text.replace(',', "") };
+                let number: f64 = number.parse().map_err(|_| ColumnParseError::ParseError)?;
+
+                match column.ty {
+                    ColumnType::Int if number.fract() == 0.0 => Ok(Some(ColumnValue::Int(number as i64))),
+                    ColumnType::Int => Err(ColumnParseError::ParseError),
+                    ColumnType::String => Ok(Some(ColumnValue::String(format!("{:.2}", number)))),
+                    _ => Ok(Some(ColumnValue::Float(number))),
+                }
+            },
+            // digits only, keeping a leading plus
+            Mapper::Phone => {
+                let digits: String = text.chars().filter(char::is_ascii_digit).collect();
+
+                if !(7..=15).contains(&digits.len()) {
+                    return Err(ColumnParseError::ParseError);
+                }
+
+                match column.ty {
+                    ColumnType::String if text.starts_with('+') => Ok(Some(ColumnValue::String(format!("+{}", digits)))),
+                    ColumnType::String => Ok(Some(ColumnValue::String(digits))),
+                    _ => digits.parse().map(|digits| Some(ColumnValue::Int(digits))).map_err(|_| ColumnParseError::ParseError),
+                }
+            },
+            // a space is taken for the `T` of date times
+            Mapper::Time(format) => {
+                let text = if format.contains('T') { text.replacen(' ', "T", 1) } else { text.to_owned() };
+
+                let valid = chrono::NaiveDateTime::parse_from_str(&text, format).is_ok()
+                    || chrono::NaiveDate::parse_from_str(&text, format).is_ok()
+                    || chrono::NaiveTime::parse_from_str(&text, format).is_ok();
+
+                if valid { Ok(Some(ColumnValue::String(text))) }
+                else { Err(ColumnParseError::ParseError) }
+            },
+        }
+    }
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Table {
     pub name: String,
     pub table: String,
@@ -169,9 +544,19 @@ impl Table {
 
         name
     }
+
+    // sort an entry's columns like the schema, so whatever is made from it reads the same every time,
+    // columns the schema doesn't know keep their order at the end
+    pub fn order(&self, entry: &mut TableEntry) {
+        let index = |name: &String| self.columns.iter()
+            .position(|column| &column.name == name)
+            .unwrap_or(usize::MAX);
+
+        entry.sort_by(|a, _, b, _| index(a).cmp(&index(b)));
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum TableDefinition {
     Single(Table),
     Family { base: Table, leaves: Vec<Table> },
@@ -179,8 +564,9 @@ pub enum TableDefinition {
 
 impl TableDefinition {
     pub fn from_vec(tables: Vec<Table>) -> Vec<Self> {
-        let (trees, _) = TableNode::into_trees(tables);
+        let (trees, rest) = TableNode::into_trees(tables);
 
+        // tables without a primary key, down to ones without any columns, are listed on their own
         trees.into_iter()
             .map(|mut tree| {
                 if let Some(leaves) = tree.pop_outer_leaves() {
@@ -190,6 +576,7 @@ impl TableDefinition {
                     TableDefinition::Single(tree.node)
                 }
             })
+            .chain(rest.into_iter().map(TableDefinition::Single))
             .collect()
     }
 
@@ -220,7 +607,8 @@ impl TableDefinition {
     }
 }
 
-pub type TableEntry = HashMap<String, Option<ColumnValue>>;
+// keeps the order its columns were put in, see `Table::order`
+pub type TableEntry = IndexMap<String, Option<ColumnValue>>;
 
 #[derive(Debug, Clone)]
 struct TableNode {
@@ -307,3 +695,110 @@ impl TableNode {
         Some(child_leaves)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(ty: ColumnType) -> TableColumn {
+        TableColumn {
+            name: "value".to_owned(),
+            ty,
+            optional: false,
+            primary_key: false,
+            foreign_keys: Vec::new(),
+            mapper: None,
+            values: None,
+            read_only: false,
+        }
+    }
+
+    #[test]
+    fn typed_numbers_keep_their_digits() {
+        let parse = |ty, text| ColumnValue::try_from_str(column(ty), text).unwrap().unwrap();
+
+        // what `str::parse` takes
+        assert_eq!(parse(ColumnType::Int, "+5"), ColumnValue::Int(5));
+        assert_eq!(parse(ColumnType::Float, ".5"), ColumnValue::Float(0.5));
+        assert_eq!(parse(ColumnType::Float, "5."), ColumnValue::Float(5.0));
+        assert_eq!(parse(ColumnType::Float, "5"), ColumnValue::Float(5.0));
+
+        // and what it would round
+        assert_eq!(parse(ColumnType::Int, "12345678901234567890").to_string(), "12345678901234567890");
+        assert_eq!(parse(ColumnType::Float, "0.12345678901234567891").to_string(), "0.12345678901234567891");
+
+        assert!(ColumnValue::try_from_str(column(ColumnType::Int), "5.5").is_err());
+        assert!(ColumnValue::try_from_str(column(ColumnType::Float), "five").is_err());
+    }
+
+    #[test]
+    fn text_toward_int_only_when_exact() {
+        let toward = |text: &str| ColumnValue::from(text).toward(ColumnType::Int);
+
+        assert_eq!(toward("7"), ColumnValue::Int(7));
+        assert_eq!(toward("-7"), ColumnValue::Int(-7));
+        assert_eq!(toward("12345678901234567890"), ColumnValue::Big("12345678901234567890".parse().unwrap()));
+
+        for text in ["007", " 7", "+7", "7.0", "1e3", "seven"] {
+            assert_eq!(toward(text), ColumnValue::from(text));
+        }
+
+        let toward = |text: &str| ColumnValue::from(text).toward(ColumnType::Float);
+
+        assert_eq!(toward("1.5"), ColumnValue::Float(1.5));
+        assert_eq!(toward("7"), ColumnValue::Float(7.0));
+        assert_eq!(toward("0.12345678901234567891"), ColumnValue::Big("0.12345678901234567891".parse().unwrap()));
+
+        for text in ["007", " 7", "1.50", "1e3", ".5", "seven"] {
+            assert_eq!(toward(text), ColumnValue::from(text));
+        }
+    }
+
+    #[test]
+    fn loose_bools_read_as_bools() {
+        let bools = [
+            (serde_json::json!(false), false),
+            (serde_json::json!(true), true),
+            (serde_json::json!(0), false),
+            (serde_json::json!(1), true),
+            (serde_json::json!("false"), false),
+            (serde_json::json!("true"), true),
+            (serde_json::json!("0"), false),
+            (serde_json::json!("1"), true),
+        ];
+
+        for (value, expected) in bools {
+            let read = ColumnValue::try_from_value(value.clone(), Some(ColumnType::Bool));
+            assert_eq!(read, Ok(Some(ColumnValue::Bool(expected))), "{}", value);
+        }
+
+        // anything else stays a mismatch, and other columns aren't touched
+        assert_eq!(ColumnValue::try_from_value(serde_json::json!(2), Some(ColumnType::Bool)), Ok(Some(ColumnValue::Int(2))));
+        assert_eq!(ColumnValue::try_from_value(serde_json::json!("yes"), Some(ColumnType::Bool)), Ok(Some(ColumnValue::from("yes"))));
+        assert_eq!(ColumnValue::try_from_value(serde_json::json!(1), Some(ColumnType::Int)), Ok(Some(ColumnValue::Int(1))));
+        assert_eq!(ColumnValue::try_from_value(serde_json::json!(1), None), Ok(Some(ColumnValue::Int(1))));
+    }
+
+    #[test]
+    fn values_serialize_like_json_values() {
+        let values = [
+            ColumnValue::from(true),
+            ColumnValue::from(-7i64),
+            ColumnValue::from(7),
+            ColumnValue::from(1.5),
+            ColumnValue::Big("12345678901234567890".parse().unwrap()),
+            ColumnValue::from("seven"),
+            ColumnValue::from("seven".to_owned()),
+            ColumnValue::Json(serde_json::json!({"a": [1, null]})),
+        ];
+
+        for value in values {
+            let text = serde_json::to_string(&value).unwrap();
+            assert_eq!(text, Value::from(value.clone()).to_string());
+
+            // and read back as the same value
+            let read = ColumnValue::try_from_value(serde_json::from_str(&text).unwrap(), Some(value.ty()));
+            assert_eq!(read, Ok(Some(value)));
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use iced::{Color, Element};
+use iced::widget::{center, container, mouse_area, opaque, stack, text, tooltip};
+
+// show content over a dimmed base, pressing outside of it produces on_blur
+pub fn modal<'a, Message>(
+    base: impl Into<Element<'a, Message>>,
+    content: impl Into<Element<'a, Message>>,
+    on_blur: Message,
+) -> Element<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    let content = container(content)
+        .padding(16)
+        .max_width(512)
+        .style(container::bordered_box);
+
+    stack![
+        base.into(),
+        opaque(
+            mouse_area(center(opaque(content)).style(|_theme| {
+                container::Style {
+                    background: Some(Color { a: 0.8, ..Color::BLACK }.into()),
+                    ..container::Style::default()
+                }
+            }))
+            .on_press(on_blur)
+        ),
+    ]
+    .into()
+}
+
+// describe an icon-only control on hover
+pub fn labelled<'a, Message>(
+    content: impl Into<Element<'a, Message>>,
+    label: impl text::IntoFragment<'a>,
+) -> Element<'a, Message>
+where
+    Message: 'a,
+{
+    let label = container(text(label).size(12))
+        .padding(4)
+        .style(container::bordered_box);
+
+    tooltip(content, label, tooltip::Position::Bottom).into()
+}